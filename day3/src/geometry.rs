@@ -0,0 +1,131 @@
+use crate::{walk, Coord};
+use std::collections::{HashMap, HashSet};
+
+// intersections and loops are only found where the route comes back to a house it already
+// visited. two diagonal steps can cross in the middle of a square without sharing a house, e.g.
+// [^>] from (0, 0) and [v>] from (0, 1), and that crossing is neither reported nor measured
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteStats {
+    pub distance: usize,
+    pub farthest: Coord,
+    pub intersections: Vec<Coord>,
+    pub first_revisit: Option<Coord>,
+    pub enclosed_area: f64,
+}
+
+fn shoelace(polygon: &[Coord]) -> i64 {
+    // twice the unsigned area of a closed polygon, the last vertex connects back to the first
+    let twice_area: i64 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+        .sum();
+    twice_area.abs()
+}
+
+pub fn analyze<I>(steps: I) -> RouteStats
+where
+    I: Iterator<Item = Coord>,
+{
+    let mut distance = 0;
+    let mut farthest = Coord::new(0, 0);
+    let mut intersections = Vec::new();
+    let mut seen = HashSet::new();
+    let mut crossed = HashSet::new();
+
+    // the loop-erased path so far and where each house sits in it, when a house comes up again
+    // everything after its first appearance is a closed loop that gets measured and removed
+    let mut path: Vec<Coord> = Vec::new();
+    let mut path_index: HashMap<Coord, usize> = HashMap::new();
+    let mut twice_area = 0;
+
+    for (n, house) in walk(steps).enumerate() {
+        distance = n;
+        if house.manhattan() > farthest.manhattan() {
+            farthest = house;
+        }
+        if !seen.insert(house) && crossed.insert(house) {
+            intersections.push(house);
+        }
+
+        match path_index.get(&house) {
+            Some(&start) => {
+                twice_area += shoelace(&path[start..]);
+                for erased in path.drain(start + 1..) {
+                    path_index.remove(&erased);
+                }
+            }
+            None => {
+                path_index.insert(house, path.len());
+                path.push(house);
+            }
+        }
+    }
+
+    RouteStats {
+        distance,
+        farthest,
+        first_revisit: intersections.first().copied(),
+        intersections,
+        enclosed_area: twice_area as f64 / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::{parse_moves, steps};
+
+    fn route(directions: &str) -> RouteStats {
+        analyze(steps(&parse_moves(directions)))
+    }
+
+    #[test]
+    fn test_straight_route() {
+        let stats = route("^^>>>v");
+        assert_eq!(stats.distance, 6);
+        assert_eq!(stats.farthest, Coord::new(3, 2));
+        assert_eq!(stats.farthest.manhattan(), 5);
+        assert!(stats.intersections.is_empty());
+        assert_eq!(stats.first_revisit, None);
+        assert_eq!(stats.enclosed_area, 0.0);
+    }
+
+    #[test]
+    fn test_loops() {
+        // a 2x2 square, then back and forth along one edge which encloses nothing
+        let stats = route("2^2>2v2<^v");
+        assert_eq!(stats.distance, 10);
+        assert_eq!(stats.first_revisit, Some(Coord::new(0, 0)));
+        assert_eq!(
+            stats.intersections,
+            vec![Coord::new(0, 0), Coord::new(0, 1)]
+        );
+        assert_eq!(stats.enclosed_area, 4.0);
+
+        // a diamond of diagonal steps covers half of its 2x2 bounding square
+        assert_eq!(route("[^>][v>][v<][^<]").enclosed_area, 2.0);
+    }
+
+    #[test]
+    fn test_figure_eight() {
+        // two unit squares that touch at the origin
+        let stats = route("^>v<v<^>");
+        assert_eq!(stats.intersections, vec![Coord::new(0, 0)]);
+        assert_eq!(stats.enclosed_area, 2.0);
+    }
+
+    #[test]
+    fn test_crossing_diagonals() {
+        // up to (1, 1), then (0, 1) and down across the first step to (1, 0), the two diagonals
+        // cross between houses so the triangle they close is not counted
+        let stats = route("[^>]<[v>]");
+        assert!(stats.intersections.is_empty());
+        assert_eq!(stats.first_revisit, None);
+        assert_eq!(stats.enclosed_area, 0.0);
+
+        // once the route reaches a house again the loop through the crossing is measured whole,
+        // the bow tie's two triangles wind in opposite directions and cancel
+        assert_eq!(route("[^>]<[v>]<").enclosed_area, 0.0);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{bounding_box, BoundingBox, Coord};
+use grid::{image, Grid};
+use std::collections::HashMap;
+
+// the most houses a picture is drawn with (64MB of counts), like the dense tracker's MAX_BITS
+const MAX_CELLS: usize = 1 << 24;
+
+fn layout(counts: &HashMap<Coord, u32>, bbox: &BoundingBox) -> Result<Grid<u32>, String> {
+    // lays the visit counts out as rows with north at the top, refusing boxes too large to hold
+    let (width, height) = (bbox.width(), bbox.height());
+    if width.checked_mul(height).is_none_or(|x| x > MAX_CELLS) {
+        return Err(format!(
+            "route spans {}x{} houses, too many to draw (at most {})",
+            width, height, MAX_CELLS
+        ));
+    }
+    let cells = (0..bbox.height())
+        .flat_map(|row| {
+            let y = bbox.max.y - row as i32;
+            (bbox.min.x..=bbox.max.x).map(move |x| *counts.get(&Coord::new(x, y)).unwrap_or(&0))
+        })
+        .collect();
+    Grid::from_vec(height, width, cells)
+}
+
+pub fn to_pgm(counts: &HashMap<Coord, u32>) -> Result<String, String> {
+    // writes a plain (P2) greyscale image where the brightest pixel is the busiest house
+    let max_value = counts.values().copied().max().unwrap_or(0).max(1);
+    let grid = match bounding_box(counts.keys()) {
+        Some(bbox) => layout(counts, &bbox)?,
+        None => Grid::new(0, 0),
+    };
+    Ok(image::to_pgm(&grid, max_value))
+}
+
+pub fn to_ascii(counts: &HashMap<Coord, u32>) -> Result<String, String> {
+    // renders the route as text, denser characters mean more visits
+    let bbox = match bounding_box(counts.keys()) {
+        Some(bbox) => bbox,
+        None => return Ok(String::new()),
+    };
+    let max_value = counts.values().copied().max().unwrap_or(0) as u64;
+
+    let grid = layout(counts, &bbox)?;
+    let mut map = String::with_capacity((grid.columns() + 1) * grid.rows());
+    for row in grid.row_slices() {
+        for &count in row {
+            map.push(image::shade(count as u64, max_value));
+        }
+        map.push('\n');
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::{parse_moves, steps};
+    use crate::visit_counts;
+
+    #[test]
+    fn test_pgm_export() {
+        let counts = visit_counts(steps(&parse_moves("^>v<^")));
+        assert_eq!(to_pgm(&counts).unwrap(), "P2\n2 2\n2\n2 1\n2 1\n");
+    }
+
+    #[test]
+    fn test_ascii_export() {
+        let counts = visit_counts(steps(&parse_moves(">>^")));
+        assert_eq!(to_ascii(&counts).unwrap(), "  @\n@@@\n");
+        assert_eq!(to_ascii(&HashMap::new()).unwrap(), "");
+    }
+
+    #[test]
+    fn test_too_large() {
+        // a single diagonal run is only a million houses but spans a million squared
+        let counts = visit_counts(steps(&parse_moves("1000000[^>]")));
+        assert!(to_pgm(&counts).unwrap_err().contains("1000001x1000001"));
+        assert!(to_ascii(&counts).is_err());
+    }
+}
@@ -1,9 +1,18 @@
-use std::collections::HashSet;
+mod geometry;
+mod heatmap;
+mod moves;
+mod tracker;
+
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::fs;
 use std::ops::Add;
 use std::process;
 
-#[derive(Hash, Eq, PartialEq, Clone, Copy)]
+use moves::{parse_moves, parse_moves_strict, steps, Move};
+use tracker::{DenseGrid, HouseTracker};
+
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug, Default)]
 struct Coord {
     x: i32,
     y: i32,
@@ -24,41 +33,104 @@ impl Coord {
     pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
+
+    pub fn manhattan(&self) -> u32 {
+        // distance from the origin when only moving along the grid
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
 }
 
-fn visit_houses<'a, I>(instructions: I) -> HashSet<Coord>
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct BoundingBox {
+    min: Coord,
+    max: Coord,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+}
+
+fn bounding_box<'a, I>(houses: I) -> Option<BoundingBox>
 where
-    I: Iterator<Item = char>,
+    I: IntoIterator<Item = &'a Coord>,
 {
-    // returns a set of houses that were visited based on instructions from the char iterator
-    let mut visited_points: HashSet<Coord> = instructions
-        .scan(Coord::new(0, 0), |current_coordinate, instruction| {
-            match instruction {
-                '^' => current_coordinate.y += 1,
-                '<' => current_coordinate.x -= 1,
-                '>' => current_coordinate.x += 1,
-                'v' => current_coordinate.y -= 1,
-                _ => (),
-            };
-            Some(*current_coordinate)
+    // returns the smallest box containing every house, or None if there were no houses
+    houses.into_iter().fold(None, |bbox, house| {
+        Some(match bbox {
+            None => BoundingBox {
+                min: *house,
+                max: *house,
+            },
+            Some(BoundingBox { min, max }) => BoundingBox {
+                min: Coord::new(min.x.min(house.x), min.y.min(house.y)),
+                max: Coord::new(max.x.max(house.x), max.y.max(house.y)),
+            },
         })
-        .collect();
+    })
+}
 
-    visited_points.insert(Coord::new(0, 0)); // put in the origin coordinate
+fn walk<I>(steps: I) -> impl Iterator<Item = Coord>
+where
+    I: Iterator<Item = Coord>,
+{
+    // yields every coordinate stepped on, starting with the origin
+    let houses = steps.scan(Coord::new(0, 0), |current_coordinate, step| {
+        *current_coordinate = *current_coordinate + step;
+        Some(*current_coordinate)
+    });
+    std::iter::once(Coord::new(0, 0)).chain(houses)
+}
+
+fn visit_houses<T, I>(steps: I) -> T
+where
+    T: HouseTracker,
+    I: Iterator<Item = Coord>,
+{
+    // returns the houses that were visited based on the unit steps from the iterator
+    let mut visited_points = T::default();
+    visited_points.visit_all(walk(steps));
     visited_points
 }
 
-fn part_one(elf_directions: &str) -> usize {
-    // make a hashmap of the coordinates santa's visited
-    let visited_points = visit_houses(elf_directions.chars());
+fn visit_counts<I>(steps: I) -> HashMap<Coord, u32>
+where
+    I: Iterator<Item = Coord>,
+{
+    // returns how many presents each house received, the origin gets one before any moves
+    let mut counts = HashMap::new();
+    for house in walk(steps) {
+        *counts.entry(house).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn part_one<T: HouseTracker>(moves: &[Move]) -> usize {
+    // track the coordinates santa's visited
+    let visited_points: T = visit_houses(steps(moves));
+    visited_points.len()
+}
+
+fn part_two<T: HouseTracker>(moves: &[Move]) -> usize {
+    // santa and robo-santa take turns, so both walks go into the same tracker. a turn is a whole
+    // move, a repeated one is walked entirely by whoever's turn it is
+    let santa: Vec<Move> = moves.iter().step_by(2).copied().collect();
+    let robo_santa: Vec<Move> = moves.iter().skip(1).step_by(2).copied().collect();
+    let mut visited_points: T = visit_houses(steps(&santa));
+    visited_points.visit_all(walk(steps(&robo_santa)));
     visited_points.len()
 }
 
-fn part_two(elf_directions: &str) -> usize {
-    // make a hashmap of the coordinates santa's visited
-    let santa_points = visit_houses(elf_directions.chars().step_by(2));
-    let robo_points = visit_houses(elf_directions.chars().skip(1).step_by(2));
-    santa_points.union(&robo_points).count()
+fn drawn(picture: Result<String, String>) -> String {
+    picture.unwrap_or_else(|err| {
+        eprintln!("Heatmap Error: {}", err);
+        process::exit(1);
+    })
 }
 
 fn main() {
@@ -67,23 +139,120 @@ fn main() {
         process::exit(1);
     });
 
-    println!("Part One Solution: {}", part_one(&input));
-    println!("Part Two Solution: {}", part_two(&input));
+    let args: Vec<String> = env::args().skip(1).collect();
+    // parsed once up front, so a strict parse error stops everything before any answer is printed
+    let moves = if args.iter().any(|x| x == "--strict") {
+        parse_moves_strict(&input).unwrap_or_else(|err| {
+            eprintln!("Direction Parse Error: {}", err);
+            process::exit(1);
+        })
+    } else {
+        parse_moves(&input)
+    };
+
+    if args.iter().any(|x| x == "--dense") {
+        println!("Part One Solution: {}", part_one::<DenseGrid>(&moves));
+        println!("Part Two Solution: {}", part_two::<DenseGrid>(&moves));
+    } else {
+        println!("Part One Solution: {}", part_one::<HashSet<Coord>>(&moves));
+        println!("Part Two Solution: {}", part_two::<HashSet<Coord>>(&moves));
+    }
     // println!("Part One Solution: {}", part_two(&presents));
+
+    let stats = geometry::analyze(steps(&moves));
+    println!(
+        "Route covers {} steps, farthest house is {} away at ({}, {})",
+        stats.distance,
+        stats.farthest.manhattan(),
+        stats.farthest.x,
+        stats.farthest.y
+    );
+    if let Some(house) = stats.first_revisit {
+        println!(
+            "First revisited house is ({}, {}), {} houses revisited, loops enclose {} square units",
+            house.x,
+            house.y,
+            stats.intersections.len(),
+            stats.enclosed_area
+        );
+    }
+
+    // optional visualizations of santa's route
+    let counts = visit_counts(steps(&moves));
+    if let Some(bbox) = bounding_box(counts.keys()) {
+        println!(
+            "Route spans {}x{} houses, busiest house visited {} times",
+            bbox.width(),
+            bbox.height(),
+            counts.values().max().unwrap_or(&0)
+        );
+    }
+    for (n, arg) in args.iter().enumerate() {
+        match arg.as_str() {
+            "--ascii" => print!("{}", drawn(heatmap::to_ascii(&counts))),
+            "--pgm" => {
+                let path = args.get(n + 1).unwrap_or_else(|| {
+                    eprintln!("--pgm requires an output path");
+                    process::exit(1);
+                });
+                fs::write(path, drawn(heatmap::to_pgm(&counts))).unwrap_or_else(|err| {
+                    eprintln!("File Write Error {}", err);
+                    process::exit(1);
+                });
+            }
+            _ => (),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    fn houses_one(directions: &str) -> usize {
+        part_one::<HashSet<Coord>>(&parse_moves(directions))
+    }
+
+    fn houses_two(directions: &str) -> usize {
+        part_two::<HashSet<Coord>>(&parse_moves(directions))
+    }
+
     #[test]
     fn test_part_one() {
         let instructions = String::from("^v^v^v^v^v");
-        assert_eq!(part_one(&instructions), 2);
-        assert_eq!(part_one("^>v"), 4);
+        assert_eq!(houses_one(&instructions), 2);
+        assert_eq!(houses_one("^>v"), 4);
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two("^v^v^v^v^v"), 11);
+        assert_eq!(houses_two("^v^v^v^v^v"), 11);
+        // santa walks all three steps north before robo-santa takes his turn east
+        assert_eq!(houses_two("3^>"), 5);
+        assert_eq!(houses_two("3^3>"), 7);
+    }
+
+    #[test]
+    fn test_extended_moves() {
+        assert_eq!(houses_one("5^5v"), houses_one("^^^^^vvvvv"));
+        assert_eq!(houses_one("[^>][v<]"), 2);
+    }
+
+    #[test]
+    fn test_visit_counts() {
+        let counts = visit_counts(steps(&parse_moves("^v^v^v^v^v")));
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&Coord::new(0, 0)], 6);
+        assert_eq!(counts[&Coord::new(0, 1)], 5);
+        assert_eq!(counts.values().sum::<u32>(), 11);
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let counts = visit_counts(steps(&parse_moves("^>>vvv<<<<")));
+        let bbox = bounding_box(counts.keys()).unwrap();
+        assert_eq!(bbox.min, Coord::new(-2, -2));
+        assert_eq!(bbox.max, Coord::new(2, 1));
+        assert_eq!((bbox.width(), bbox.height()), (5, 4));
+        assert_eq!(bounding_box(&[]), None);
     }
 }
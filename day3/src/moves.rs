@@ -0,0 +1,225 @@
+use crate::Coord;
+use std::fmt;
+
+// the largest repeat count accepted, every repeated step is expanded one house at a time so a
+// count needs to stay small enough to walk
+const MAX_REPEAT: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Move {
+    step: Coord,
+    count: usize,
+}
+
+impl Move {
+    pub fn new(step: Coord, count: usize) -> Self {
+        Self { step, count }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MoveErrorKind {
+    UnknownCharacter(char),
+    InvalidDiagonal(String),
+    UnterminatedDiagonal,
+    DanglingCount,
+    CountOverflow,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MoveError {
+    pub position: usize,
+    pub kind: MoveErrorKind,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            MoveErrorKind::UnknownCharacter(c) => write!(f, "unknown direction '{}'", c),
+            MoveErrorKind::InvalidDiagonal(s) => write!(f, "invalid diagonal [{}]", s),
+            MoveErrorKind::UnterminatedDiagonal => write!(f, "diagonal is missing a closing ]"),
+            MoveErrorKind::DanglingCount => write!(f, "repeat count is not followed by a move"),
+            MoveErrorKind::CountOverflow => {
+                write!(f, "repeat count is larger than {}", MAX_REPEAT)
+            }
+        }?;
+        write!(f, " at position {}", self.position)
+    }
+}
+
+fn cardinal(direction: char) -> Option<Coord> {
+    match direction {
+        '^' => Some(Coord::new(0, 1)),
+        '<' => Some(Coord::new(-1, 0)),
+        '>' => Some(Coord::new(1, 0)),
+        'v' => Some(Coord::new(0, -1)),
+        _ => None,
+    }
+}
+
+fn diagonal(contents: &str) -> Option<Coord> {
+    // a diagonal is one vertical and one horizontal direction in either order, e.g. [^>] or [>^]
+    let steps: Vec<Coord> = contents.chars().map(cardinal).collect::<Option<_>>()?;
+    match steps.as_slice() {
+        [a, b] if (a.x == 0) != (b.x == 0) => Some(*a + *b),
+        _ => None,
+    }
+}
+
+fn parse(input: &str, strict: bool) -> Result<Vec<Move>, MoveError> {
+    let mut moves = Vec::new();
+    let mut chars = input.chars().enumerate();
+    // the pending repeat count and the position it started at, None once it's too large
+    let mut count: Option<(usize, Option<usize>)> = None;
+
+    while let Some((position, c)) = chars.next() {
+        let step = match c {
+            '0'..='9' => {
+                let digit = c.to_digit(10).unwrap() as usize;
+                let (start, value) = count.unwrap_or((position, Some(0)));
+                let value = value
+                    .and_then(|x| x.checked_mul(10))
+                    .and_then(|x| x.checked_add(digit))
+                    .filter(|&x| x <= MAX_REPEAT);
+                if value.is_none() && strict {
+                    return Err(MoveError {
+                        position: start,
+                        kind: MoveErrorKind::CountOverflow,
+                    });
+                }
+                count = Some((start, value));
+                continue;
+            }
+            '[' => {
+                let mut contents = String::new();
+                let mut closed = false;
+                for (_, c) in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    contents.push(c);
+                }
+                match (closed, diagonal(&contents)) {
+                    (true, Some(step)) => Some(step),
+                    _ if !strict => None,
+                    (false, _) => {
+                        return Err(MoveError {
+                            position,
+                            kind: MoveErrorKind::UnterminatedDiagonal,
+                        })
+                    }
+                    (true, None) => {
+                        return Err(MoveError {
+                            position,
+                            kind: MoveErrorKind::InvalidDiagonal(contents),
+                        })
+                    }
+                }
+            }
+            c if c.is_whitespace() => continue,
+            c => match cardinal(c) {
+                Some(step) => Some(step),
+                None if strict => {
+                    return Err(MoveError {
+                        position,
+                        kind: MoveErrorKind::UnknownCharacter(c),
+                    })
+                }
+                None => continue,
+            },
+        };
+
+        // a zero repeat count leaves santa where he is, one that's too large is skipped like any
+        // other construct that isn't understood and the move is made once
+        match (step, count.map_or(Some(1), |(_, value)| value).unwrap_or(1)) {
+            (Some(step), n) if n > 0 => moves.push(Move::new(step, n)),
+            _ => (),
+        }
+        count = None;
+    }
+
+    match count {
+        Some((position, _)) if strict => Err(MoveError {
+            position,
+            kind: MoveErrorKind::DanglingCount,
+        }),
+        _ => Ok(moves),
+    }
+}
+
+pub fn parse_moves(input: &str) -> Vec<Move> {
+    // anything that isn't understood is skipped, matching the original puzzle behavior
+    parse(input, false).unwrap_or_default()
+}
+
+pub fn parse_moves_strict(input: &str) -> Result<Vec<Move>, MoveError> {
+    // like parse_moves but any character or construct that isn't understood is an error
+    parse(input, true)
+}
+
+pub fn steps(moves: &[Move]) -> impl Iterator<Item = Coord> + Clone + '_ {
+    // expands repeat counts into one unit step per house
+    moves
+        .iter()
+        .flat_map(|m| std::iter::repeat_n(m.step, m.count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeat_and_diagonal() {
+        let moves = parse_moves_strict("3^[v>]2[<^]\n").unwrap();
+        assert_eq!(
+            moves,
+            vec![
+                Move::new(Coord::new(0, 1), 3),
+                Move::new(Coord::new(1, -1), 1),
+                Move::new(Coord::new(-1, 1), 2),
+            ]
+        );
+        assert_eq!(steps(&moves).count(), 6);
+        assert_eq!(parse_moves("0^>"), vec![Move::new(Coord::new(1, 0), 1)]);
+    }
+
+    #[test]
+    fn test_lenient_skips_unknown() {
+        assert_eq!(parse_moves("^x>"), parse_moves("^>"));
+        assert_eq!(parse_moves("[^^]>3"), parse_moves(">"));
+    }
+
+    #[test]
+    fn test_strict_errors() {
+        let err = parse_moves_strict("^^x").unwrap_err();
+        assert_eq!(err.position, 2);
+        assert_eq!(err.kind, MoveErrorKind::UnknownCharacter('x'));
+        assert_eq!(err.to_string(), "unknown direction 'x' at position 2");
+
+        let kind = |s| parse_moves_strict(s).unwrap_err().kind;
+        assert_eq!(
+            kind("^[^^]"),
+            MoveErrorKind::InvalidDiagonal("^^".to_string())
+        );
+        assert_eq!(kind("[^>"), MoveErrorKind::UnterminatedDiagonal);
+        assert_eq!(kind("^12"), MoveErrorKind::DanglingCount);
+        assert_eq!(
+            kind("99999999999999999999999^"),
+            MoveErrorKind::CountOverflow
+        );
+        assert_eq!(kind("99999999999^"), MoveErrorKind::CountOverflow);
+        assert_eq!(kind("1000001>"), MoveErrorKind::CountOverflow);
+        assert_eq!(
+            parse_moves_strict("1000000>"),
+            Ok(vec![Move::new(Coord::new(1, 0), MAX_REPEAT)])
+        );
+    }
+
+    #[test]
+    fn test_lenient_drops_large_counts() {
+        assert_eq!(parse_moves("99999999999999999999999^"), parse_moves("^"));
+        assert_eq!(parse_moves("99999999999^>"), parse_moves("^>"));
+        assert_eq!(steps(&parse_moves("1000001>2^")).count(), 3);
+    }
+}
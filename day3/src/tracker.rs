@@ -0,0 +1,287 @@
+use crate::Coord;
+use std::collections::HashSet;
+
+// how far past the first house the dense grid reaches before it has to grow
+const INITIAL_REACH: i32 = 32;
+// the most bits the dense grid allocates (32MB), past that it falls back to a hashset
+const MAX_BITS: usize = 1 << 28;
+
+pub trait HouseTracker: Default {
+    fn visit(&mut self, house: Coord);
+    fn len(&self) -> usize;
+
+    fn visit_all<I>(&mut self, houses: I)
+    where
+        I: IntoIterator<Item = Coord>,
+    {
+        for house in houses {
+            self.visit(house);
+        }
+    }
+}
+
+impl HouseTracker for HashSet<Coord> {
+    fn visit(&mut self, house: Coord) {
+        self.insert(house);
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+}
+
+#[derive(Default)]
+pub struct DenseGrid {
+    min: Coord,
+    width: usize,
+    height: usize,
+    bits: Vec<u64>,
+    visited: usize,
+    // every house ends up here once the box would grow past MAX_BITS
+    sparse: Option<HashSet<Coord>>,
+}
+
+fn read_bits(bits: &[u64], start: usize, len: usize) -> u64 {
+    // up to 64 bits starting at any bit, lowest first
+    let (word, offset) = (start / 64, start % 64);
+    let mut value = bits[word] >> offset;
+    if offset > 0 && offset + len > 64 {
+        value |= bits[word + 1] << (64 - offset);
+    }
+    if len < 64 {
+        value & ((1 << len) - 1)
+    } else {
+        value
+    }
+}
+
+fn or_bits(bits: &mut [u64], start: usize, len: usize, value: u64) {
+    let (word, offset) = (start / 64, start % 64);
+    bits[word] |= value << offset;
+    if offset > 0 && offset + len > 64 {
+        bits[word + 1] |= value >> (64 - offset);
+    }
+}
+
+impl DenseGrid {
+    fn index(&self, house: &Coord) -> Option<usize> {
+        // bit index of the house, or None if it's outside the allocated box
+        let x = house.x.checked_sub(self.min.x)?;
+        let y = house.y.checked_sub(self.min.y)?;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    fn is_set(&self, index: usize) -> bool {
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn grow_to(&mut self, house: Coord) {
+        // extends the box to cover the house, at least doubling along each axis that grows
+        let (min, width, height) = if self.width == 0 {
+            let reach = 2 * INITIAL_REACH as usize;
+            let min = Coord::new(house.x - INITIAL_REACH, house.y - INITIAL_REACH);
+            (min, reach, reach)
+        } else {
+            let max_x = self.min.x + self.width as i32 - 1;
+            let max_y = self.min.y + self.height as i32 - 1;
+            let (w, h) = (self.width as i32, self.height as i32);
+            let min_x = if house.x < self.min.x {
+                house.x.min(self.min.x - w)
+            } else {
+                self.min.x
+            };
+            let min_y = if house.y < self.min.y {
+                house.y.min(self.min.y - h)
+            } else {
+                self.min.y
+            };
+            let max_x = if house.x > max_x {
+                house.x.max(max_x + w)
+            } else {
+                max_x
+            };
+            let max_y = if house.y > max_y {
+                house.y.max(max_y + h)
+            } else {
+                max_y
+            };
+            let min = Coord::new(min_x, min_y);
+            (
+                min,
+                (max_x - min_x + 1) as usize,
+                (max_y - min_y + 1) as usize,
+            )
+        };
+
+        let cells = match width.checked_mul(height).filter(|&x| x <= MAX_BITS) {
+            Some(cells) => cells,
+            None => {
+                self.sparse = Some(self.houses().collect());
+                self.bits = Vec::new();
+                return;
+            }
+        };
+        let mut grown = DenseGrid {
+            min,
+            width,
+            height,
+            bits: vec![0; cells.div_ceil(64)],
+            visited: self.visited,
+            sparse: None,
+        };
+        // copy over every house we've already seen a row at a time, up to a word per step
+        for y in 0..self.height {
+            let from = y * self.width;
+            let to = grown
+                .index(&Coord::new(self.min.x, self.min.y + y as i32))
+                .unwrap();
+            for x in (0..self.width).step_by(64) {
+                let len = (self.width - x).min(64);
+                or_bits(
+                    &mut grown.bits,
+                    to + x,
+                    len,
+                    read_bits(&self.bits, from + x, len),
+                );
+            }
+        }
+        *self = grown;
+    }
+
+    fn houses(&self) -> impl Iterator<Item = Coord> + '_ {
+        // skips empty words, so a mostly unvisited box is cheap to walk
+        let set_bits = self.bits.iter().enumerate().flat_map(|(n, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                let bit = word.trailing_zeros() as usize;
+                word &= word.wrapping_sub(1);
+                (bit < 64).then_some(n * 64 + bit)
+            })
+        });
+        set_bits.map(move |n| {
+            let (x, y) = (n % self.width, n / self.width);
+            Coord::new(self.min.x + x as i32, self.min.y + y as i32)
+        })
+    }
+}
+
+impl HouseTracker for DenseGrid {
+    fn visit(&mut self, house: Coord) {
+        if self.sparse.is_none() && self.index(&house).is_none() {
+            self.grow_to(house);
+        }
+        if let Some(houses) = self.sparse.as_mut() {
+            if houses.insert(house) {
+                self.visited += 1;
+            }
+            return;
+        }
+        let index = self.index(&house).unwrap();
+        if !self.is_set(index) {
+            self.bits[index / 64] |= 1 << (index % 64);
+            self.visited += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::{parse_moves, steps};
+    use crate::{part_one, part_two, walk};
+    use grid::lcg::Lcg;
+    use std::time::Instant;
+
+    fn random_directions(length: usize, seed: u64) -> String {
+        let mut lcg = Lcg::new(seed);
+        (0..length)
+            .map(|_| *lcg.choose(&['^', 'v', '<', '>']))
+            .collect()
+    }
+
+    #[test]
+    fn test_dense_grid_growth() {
+        let mut grid = DenseGrid::default();
+        let houses = vec![Coord::new(0, 0), Coord::new(500, -300), Coord::new(0, 0)];
+        grid.visit_all(houses.clone());
+        assert_eq!(grid.len(), 2);
+
+        // revisiting after the grid has grown shouldn't count anything twice
+        grid.visit_all(houses);
+        assert_eq!(grid.len(), 2);
+        grid.visit(Coord::new(-10_000, 10_000));
+        assert_eq!(grid.len(), 3);
+        assert!(grid.sparse.is_none());
+
+        // a box too large to allocate moves every house into the hashset
+        grid.visit(Coord::new(1_000_000_000, -1_000_000_000));
+        assert!(grid.sparse.is_some());
+        grid.visit_all([Coord::new(500, -300), Coord::new(1, 1)]);
+        assert_eq!(grid.len(), 5);
+    }
+
+    #[test]
+    fn test_dense_falls_back() {
+        let moves = parse_moves("300000>300000^");
+        assert_eq!(part_one::<DenseGrid>(&moves), 600_001);
+        assert_eq!(
+            part_two::<DenseGrid>(&moves),
+            part_two::<HashSet<Coord>>(&moves)
+        );
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        for seed in 0..20 {
+            let directions = random_directions(5000, seed);
+            let moves = parse_moves(&directions);
+            let mut sparse: HashSet<Coord> = HashSet::new();
+            let mut dense = DenseGrid::default();
+            sparse.visit_all(walk(steps(&moves)));
+            dense.visit_all(walk(steps(&moves)));
+            assert_eq!(HouseTracker::len(&sparse), dense.len());
+
+            // every house the hashset saw should already be in the dense grid
+            dense.visit_all(sparse.iter().copied());
+            assert_eq!(HouseTracker::len(&sparse), dense.len());
+
+            assert_eq!(
+                part_one::<HashSet<Coord>>(&moves),
+                part_one::<DenseGrid>(&moves)
+            );
+            assert_eq!(
+                part_two::<HashSet<Coord>>(&moves),
+                part_two::<DenseGrid>(&moves)
+            );
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_backends() {
+        // run with `cargo test --release -p day3 -- --ignored --nocapture`
+        let directions = random_directions(20_000_000, 2015);
+        let moves = parse_moves(&directions);
+
+        let start = Instant::now();
+        let mut sparse: HashSet<Coord> = HashSet::new();
+        sparse.visit_all(walk(steps(&moves)));
+        let sparse_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut dense = DenseGrid::default();
+        dense.visit_all(walk(steps(&moves)));
+        let dense_time = start.elapsed();
+
+        assert_eq!(HouseTracker::len(&sparse), dense.len());
+        println!("HashSet:   {:?} for {} houses", sparse_time, sparse.len());
+        println!("DenseGrid: {:?} for {} houses", dense_time, dense.len());
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// each line of the file is `<search> <nonce>`, where every nonce below <nonce> has been searched
+pub struct Checkpoints {
+    path: PathBuf,
+    entries: BTreeMap<String, u64>,
+}
+
+impl Checkpoints {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        // a missing file just means nothing has been searched yet
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.to_string()),
+        };
+        let entries = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| match line.rsplit_once(' ') {
+                Some((search, nonce)) => nonce
+                    .parse()
+                    .map(|nonce| (search.to_string(), nonce))
+                    .map_err(|_| format!("could not parse nonce from {}", line)),
+                None => Err(format!("could not parse checkpoint {}", line)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn searched_to(&self, search: &str) -> Option<u64> {
+        self.entries.get(search).copied()
+    }
+
+    pub fn update(&mut self, search: &str, searched_to: u64) -> io::Result<()> {
+        self.entries.insert(search.to_string(), searched_to);
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|(search, nonce)| format!("{} {}\n", search, nonce))
+            .collect();
+        // write then rename so an interrupted save never leaves a truncated checkpoint
+        let partial = self.path.with_extension("partial");
+        fs::write(&partial, contents)?;
+        fs::rename(&partial, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn test_round_trip() {
+        let path = env::temp_dir().join(format!("day4-checkpoint-{}.txt", process::id()));
+        let mut checkpoints = Checkpoints::load(&path).unwrap();
+        assert_eq!(checkpoints.searched_to("md5 abcdef prefix:00000"), None);
+
+        checkpoints.update("md5 abcdef prefix:00000", 4096).unwrap();
+        checkpoints.update("sha1 abcdef bits:20", 100).unwrap();
+        checkpoints.update("md5 abcdef prefix:00000", 8192).unwrap();
+
+        let reloaded = Checkpoints::load(&path).unwrap();
+        assert_eq!(reloaded.searched_to("md5 abcdef prefix:00000"), Some(8192));
+        assert_eq!(reloaded.searched_to("sha1 abcdef bits:20"), Some(100));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bad_checkpoint() {
+        let path = env::temp_dir().join(format!("day4-bad-checkpoint-{}.txt", process::id()));
+        fs::write(&path, "md5 abcdef prefix:00000 lots\n").unwrap();
+        assert!(Checkpoints::load(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Difficulty {
+    LeadingZeroBits(u32),
+    HexPrefix(Vec<u8>),
+    HexSuffix(Vec<u8>),
+    BelowTarget(Vec<u8>),
+}
+
+fn hex_nibbles(key: &str) -> Result<Vec<u8>, String> {
+    // converts hex text into nibble values, hex digests are lowercase so uppercase is rejected
+    key.chars()
+        .map(|c| match c {
+            '0'..='9' | 'a'..='f' => Ok(c.to_digit(16).unwrap() as u8),
+            _ => Err(format!("'{}' is not a lowercase hex digit in {}", c, key)),
+        })
+        .collect()
+}
+
+fn nibble(digest: &[u8], n: usize) -> u8 {
+    let byte = digest[n / 2];
+    if n.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0x0f
+    }
+}
+
+impl Difficulty {
+    pub fn hex_prefix(key: &str) -> Result<Self, String> {
+        hex_nibbles(key).map(Self::HexPrefix)
+    }
+
+    pub fn is_possible(&self, digest_bytes: usize) -> bool {
+        // whether any digest of this length could ever meet the difficulty
+        match self {
+            Self::LeadingZeroBits(bits) => *bits as usize <= 8 * digest_bytes,
+            Self::HexPrefix(nibbles) | Self::HexSuffix(nibbles) => {
+                nibbles.len() <= 2 * digest_bytes
+            }
+            Self::BelowTarget(target) => {
+                target.len() <= 2 * digest_bytes && target.iter().any(|&x| x > 0)
+            }
+        }
+    }
+
+    pub fn is_met(&self, digest: &[u8]) -> bool {
+        if !self.is_possible(digest.len()) {
+            return false;
+        }
+        match self {
+            Self::LeadingZeroBits(bits) => {
+                let whole_bytes = *bits as usize / 8;
+                let remainder = bits % 8;
+                digest[..whole_bytes].iter().all(|&x| x == 0)
+                    && (remainder == 0 || digest[whole_bytes].leading_zeros() >= remainder)
+            }
+            Self::HexPrefix(nibbles) => nibbles
+                .iter()
+                .enumerate()
+                .all(|(n, &x)| nibble(digest, n) == x),
+            Self::HexSuffix(nibbles) => {
+                let offset = 2 * digest.len() - nibbles.len();
+                nibbles
+                    .iter()
+                    .enumerate()
+                    .all(|(n, &x)| nibble(digest, offset + n) == x)
+            }
+            Self::BelowTarget(target) => {
+                // the digest truncated to the target's length is compared as a big-endian number
+                let leading = (0..target.len()).map(|n| nibble(digest, n));
+                leading.cmp(target.iter().copied()) == Ordering::Less
+            }
+        }
+    }
+}
+
+fn hex_text(nibbles: &[u8]) -> String {
+    nibbles
+        .iter()
+        .map(|&x| std::char::from_digit(x as u32, 16).unwrap())
+        .collect()
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // written in the same form FromStr reads
+        match self {
+            Self::LeadingZeroBits(bits) => write!(f, "bits:{}", bits),
+            Self::HexPrefix(nibbles) => write!(f, "prefix:{}", hex_text(nibbles)),
+            Self::HexSuffix(nibbles) => write!(f, "suffix:{}", hex_text(nibbles)),
+            Self::BelowTarget(target) => write!(f, "below:{}", hex_text(target)),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // accepts bits:N, prefix:HEX, suffix:HEX, below:HEX, or a bare hex prefix like 00000
+        match s.split_once(':') {
+            Some(("bits", n)) => n
+                .parse()
+                .map(Self::LeadingZeroBits)
+                .map_err(|_| format!("could not parse bit count from {}", s)),
+            Some(("prefix", key)) => Self::hex_prefix(key),
+            Some(("suffix", key)) => hex_nibbles(key).map(Self::HexSuffix),
+            Some(("below", key)) => hex_nibbles(key).map(Self::BelowTarget),
+            Some((kind, _)) => Err(format!("unknown difficulty kind {}", kind)),
+            None => Self::hex_prefix(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leading_zero_bits() {
+        let digest = [0x00, 0x1f, 0xff];
+        assert!(Difficulty::LeadingZeroBits(0).is_met(&digest));
+        assert!(Difficulty::LeadingZeroBits(11).is_met(&digest));
+        assert!(!Difficulty::LeadingZeroBits(12).is_met(&digest));
+        assert!(!Difficulty::LeadingZeroBits(25).is_met(&digest));
+    }
+
+    #[test]
+    fn test_hex_patterns() {
+        let digest = [0x00, 0x0a, 0xbc];
+        assert!(Difficulty::hex_prefix("000a").unwrap().is_met(&digest));
+        assert!(!Difficulty::hex_prefix("0000").unwrap().is_met(&digest));
+        assert!("suffix:abc".parse::<Difficulty>().unwrap().is_met(&digest));
+        assert!(!"suffix:bbc".parse::<Difficulty>().unwrap().is_met(&digest));
+        assert!(!"suffix:0000abc"
+            .parse::<Difficulty>()
+            .unwrap()
+            .is_met(&digest));
+    }
+
+    #[test]
+    fn test_below_target() {
+        let digest = [0x00, 0x0a, 0xbc];
+        assert!("below:001".parse::<Difficulty>().unwrap().is_met(&digest));
+        assert!("below:000b".parse::<Difficulty>().unwrap().is_met(&digest));
+        assert!(!"below:000a".parse::<Difficulty>().unwrap().is_met(&digest));
+        assert!(!"below:000".parse::<Difficulty>().unwrap().is_met(&digest));
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!("00000".parse(), Ok(Difficulty::HexPrefix(vec![0; 5])));
+        assert_eq!("bits:20".parse(), Ok(Difficulty::LeadingZeroBits(20)));
+        assert!("bits:many".parse::<Difficulty>().is_err());
+        assert!("prefix:00G".parse::<Difficulty>().is_err());
+        assert!("above:00".parse::<Difficulty>().is_err());
+
+        for spec in ["bits:20", "prefix:00abc", "suffix:f", "below:0001"].iter() {
+            assert_eq!(spec.parse::<Difficulty>().unwrap().to_string(), *spec);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use crate::difficulty::Difficulty;
+use crate::hasher::HashAlgorithm;
+use crypto::digest::Digest;
+use crypto::md5::Md5;
+use crypto::sha1::Sha1;
+use crypto::sha2::Sha256;
+use std::ops::Range;
+
+// enough room for the digits of u64::MAX
+const MAX_DIGITS: usize = 20;
+// large enough for the longest supported digest (sha-256)
+const MAX_DIGEST_BYTES: usize = 32;
+
+fn write_nonce(mut nonce: u64, buffer: &mut [u8; MAX_DIGITS]) -> &[u8] {
+    // writes the decimal digits into the back of the buffer and returns the written part
+    let mut start = MAX_DIGITS;
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (nonce % 10) as u8;
+        nonce /= 10;
+        if nonce == 0 {
+            return &buffer[start..];
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Match {
+    pub nonce: u64,
+    pub digest: Vec<u8>,
+}
+
+impl Match {
+    pub fn hex(&self) -> String {
+        self.digest.iter().map(|x| format!("{:02x}", x)).collect()
+    }
+}
+
+struct Matches<'a, D> {
+    prefix_state: D,
+    digest_bytes: usize,
+    difficulty: &'a Difficulty,
+    nonces: Range<u64>,
+    digits: [u8; MAX_DIGITS],
+}
+
+impl<D: Digest + Copy> Iterator for Matches<'_, D> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        // only a match allocates, every other nonce is hashed entirely on the stack
+        let mut digest = [0u8; MAX_DIGEST_BYTES];
+        let digest = &mut digest[..self.digest_bytes];
+        for x in self.nonces.by_ref() {
+            let mut sh = self.prefix_state;
+            sh.input(write_nonce(x, &mut self.digits));
+            sh.result(digest);
+            if self.difficulty.is_met(digest) {
+                return Some(Match {
+                    nonce: x,
+                    digest: digest.to_vec(),
+                });
+            }
+        }
+        None
+    }
+}
+
+fn matches<'a, D: Digest + Copy>(
+    mut prefix_state: D,
+    base: &str,
+    difficulty: &'a Difficulty,
+    starting_index: u64,
+    end_index: u64,
+) -> Matches<'a, D> {
+    // the base is hashed once, every nonce starts from a copy of that state
+    prefix_state.input(base.as_bytes());
+    let digest_bytes = prefix_state.output_bytes();
+    // an impossible difficulty gets an empty range rather than hashing for nothing
+    let end_index = match difficulty.is_possible(digest_bytes) {
+        true => end_index,
+        false => starting_index,
+    };
+    Matches {
+        prefix_state,
+        digest_bytes,
+        difficulty,
+        nonces: starting_index..end_index,
+        digits: [0u8; MAX_DIGITS],
+    }
+}
+
+pub fn find_all<'a>(
+    algorithm: HashAlgorithm,
+    base: &str,
+    difficulty: &'a Difficulty,
+    starting_index: u64,
+    end_index: u64,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    // every nonce in the range meeting the difficulty, in increasing order
+    match algorithm {
+        HashAlgorithm::Md5 => Box::new(matches(
+            Md5::new(),
+            base,
+            difficulty,
+            starting_index,
+            end_index,
+        )),
+        HashAlgorithm::Sha1 => Box::new(matches(
+            Sha1::new(),
+            base,
+            difficulty,
+            starting_index,
+            end_index,
+        )),
+        HashAlgorithm::Sha256 => Box::new(matches(
+            Sha256::new(),
+            base,
+            difficulty,
+            starting_index,
+            end_index,
+        )),
+    }
+}
+
+pub fn find_match(
+    algorithm: HashAlgorithm,
+    base: &str,
+    difficulty: &Difficulty,
+    starting_index: u64,
+    end_index: u64,
+) -> Option<u64> {
+    find_all(algorithm, base, difficulty, starting_index, end_index)
+        .next()
+        .map(|x| x.nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn find_valid_checksum_reference(
+        algorithm: HashAlgorithm,
+        base: &str,
+        search_key: &str,
+        starting_index: u64,
+        end_index: u64,
+    ) -> Option<u64> {
+        // the original implementation, formatting and hex encoding every candidate
+        let mut sh = algorithm.hasher();
+        (starting_index..end_index).find(|&x| {
+            let out_hash = sh.hex_digest(&format!("{}{}", base, x));
+            out_hash.starts_with(search_key)
+        })
+    }
+
+    #[test]
+    fn test_write_nonce() {
+        let mut buffer = [0u8; MAX_DIGITS];
+        assert_eq!(write_nonce(0, &mut buffer), b"0");
+        assert_eq!(write_nonce(609043, &mut buffer), b"609043");
+        assert_eq!(
+            write_nonce(u64::MAX, &mut buffer),
+            u64::MAX.to_string().as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        let algorithms = [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha1,
+            HashAlgorithm::Sha256,
+        ];
+        for algorithm in algorithms.iter() {
+            for key in ["0", "00", "000", "abc", "f0"].iter() {
+                let difficulty = Difficulty::hex_prefix(key).unwrap();
+                assert_eq!(
+                    find_match(*algorithm, "abcdef", &difficulty, 0, 20000),
+                    find_valid_checksum_reference(*algorithm, "abcdef", key, 0, 20000),
+                    "{:?} {}",
+                    algorithm,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_all() {
+        let difficulty = Difficulty::hex_prefix("000").unwrap();
+        let matches: Vec<Match> =
+            find_all(HashAlgorithm::Md5, "abcdef", &difficulty, 0, 20000).collect();
+
+        // every nonce the slow way finds should show up exactly once, with its digest
+        let mut sh = HashAlgorithm::Md5.hasher();
+        let expected: Vec<(u64, String)> = (0..20000)
+            .map(|x| (x, sh.hex_digest(&format!("abcdef{}", x))))
+            .filter(|(_, hash)| hash.starts_with("000"))
+            .collect();
+        let actual: Vec<(u64, String)> = matches.iter().map(|x| (x.nonce, x.hex())).collect();
+        assert_eq!(actual, expected);
+        assert!(actual.len() > 1);
+    }
+
+    #[test]
+    fn test_find_all_impossible() {
+        let difficulty = Difficulty::LeadingZeroBits(257);
+        assert_eq!(
+            find_all(HashAlgorithm::Sha256, "abcdef", &difficulty, 0, u64::MAX).count(),
+            0
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_against_reference() {
+        // run with `cargo test --release -p day4 -- --ignored --nocapture`
+        let start = Instant::now();
+        let reference =
+            find_valid_checksum_reference(HashAlgorithm::Md5, "iwrupvqb", "000000", 0, u64::MAX);
+        let reference_time = start.elapsed();
+
+        let start = Instant::now();
+        let difficulty = Difficulty::hex_prefix("000000").unwrap();
+        let fast = find_match(HashAlgorithm::Md5, "iwrupvqb", &difficulty, 0, u64::MAX);
+        let fast_time = start.elapsed();
+
+        assert_eq!(reference, fast);
+        println!("reference: {:?}", reference_time);
+        println!("fast path: {:?}", fast_time);
+    }
+}
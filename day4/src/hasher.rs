@@ -0,0 +1,120 @@
+use crypto::digest::Digest;
+use crypto::md5::Md5;
+use crypto::sha1::Sha1;
+use crypto::sha2::Sha256;
+use std::fmt;
+use std::str::FromStr;
+
+pub trait Hasher {
+    fn hex_digest(&mut self, input: &str) -> String;
+}
+
+impl<D: Digest> Hasher for D {
+    fn hex_digest(&mut self, input: &str) -> String {
+        self.input_str(input);
+        let out_hash = self.result_str();
+        self.reset(); // reset so the hasher can be reused for the next input
+        out_hash
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub fn hasher(&self) -> Box<dyn Hasher + Send> {
+        match self {
+            Self::Md5 => Box::new(Md5::new()),
+            Self::Sha1 => Box::new(Sha1::new()),
+            Self::Sha256 => Box::new(Sha256::new()),
+        }
+    }
+
+    pub fn output_bytes(&self) -> usize {
+        match self {
+            Self::Md5 => 16,
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Md5 => "md5",
+            Self::Sha1 => "sha1",
+            Self::Sha256 => "sha256",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "md5" => Ok(Self::Md5),
+            "sha1" | "sha-1" => Ok(Self::Sha1),
+            "sha256" | "sha-256" => Ok(Self::Sha256),
+            _ => Err(format!("unknown hash algorithm {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let vectors = [
+            (HashAlgorithm::Md5, "", "d41d8cd98f00b204e9800998ecf8427e"),
+            (
+                HashAlgorithm::Md5,
+                "abc",
+                "900150983cd24fb0d6963f7d28e17f72",
+            ),
+            (
+                HashAlgorithm::Sha1,
+                "",
+                "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            ),
+            (
+                HashAlgorithm::Sha1,
+                "abc",
+                "a9993e364706816aba3e25717850c26c9cd0d89d",
+            ),
+            (
+                HashAlgorithm::Sha256,
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                HashAlgorithm::Sha256,
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+        ];
+        for (algorithm, input, expected) in vectors.iter() {
+            let mut hasher = algorithm.hasher();
+            assert_eq!(hasher.hex_digest(input), *expected);
+            assert_eq!(expected.len(), 2 * algorithm.output_bytes());
+            // hashing twice shouldn't carry any state over
+            assert_eq!(hasher.hex_digest(input), *expected);
+        }
+    }
+
+    #[test]
+    fn test_algorithm_parsing() {
+        assert_eq!("MD5".parse(), Ok(HashAlgorithm::Md5));
+        assert_eq!("sha-1".parse(), Ok(HashAlgorithm::Sha1));
+        assert_eq!("sha256".parse(), Ok(HashAlgorithm::Sha256));
+        assert!("crc32".parse::<HashAlgorithm>().is_err());
+    }
+}
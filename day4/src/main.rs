@@ -1,61 +1,237 @@
-use crypto::digest::Digest;
-use crypto::md5::Md5;
-use std::sync::mpsc;
-use std::thread;
+mod checkpoint;
+mod difficulty;
+mod fast;
+mod hasher;
+mod search;
+
+use checkpoint::Checkpoints;
+use difficulty::Difficulty;
+use hasher::HashAlgorithm;
+use search::{default_threads, parallel_search_with_progress};
+use std::env;
+use std::process;
+use std::time::Duration;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+struct SearchOptions {
+    algorithm: HashAlgorithm,
+    threads: usize,
+    checkpoints: Option<Checkpoints>,
+    resume: bool,
+}
 
 fn find_valid_checksum(
+    algorithm: HashAlgorithm,
     base: &str,
-    search_key: &str,
+    difficulty: &Difficulty,
     starting_index: u64,
     end_index: u64,
 ) -> Option<u64> {
-    let mut sh = Md5::new(); //make a new md5 hash
-    (starting_index..end_index).find(|&x| {
-        sh.input_str(&format!("{}{}", base, x)); // make our hash
-        let out_hash = sh.result_str();
-        sh.reset(); // reset the hash
-        out_hash.starts_with(search_key)
-    })
+    // compares digest bytes directly instead of formatting and hex encoding every candidate
+    fast::find_match(algorithm, base, difficulty, starting_index, end_index)
+}
+
+fn search(
+    options: &mut SearchOptions,
+    base: &str,
+    difficulty: &Difficulty,
+    starting_index: u64,
+) -> Option<u64> {
+    // runs a parallel search that prints progress and keeps the checkpoint file up to date
+    let label = format!("{} {} {}", options.algorithm, base, difficulty);
+    let resume_from = match (&options.checkpoints, options.resume) {
+        (Some(checkpoints), true) => checkpoints.searched_to(&label),
+        _ => None,
+    };
+    let start = match resume_from {
+        Some(x) if x > starting_index => {
+            eprintln!("Resuming {} from {}", label, x);
+            x
+        }
+        _ => starting_index,
+    };
+
+    let checkpoints = &mut options.checkpoints;
+    parallel_search_with_progress(
+        options.algorithm,
+        base,
+        difficulty,
+        start,
+        u64::MAX,
+        options.threads,
+        PROGRESS_INTERVAL,
+        &mut |progress| {
+            if !progress.finished {
+                eprintln!(
+                    "{}: searched below {}, {:.0} hashes/sec",
+                    label, progress.searched_to, progress.hashes_per_second
+                );
+            }
+            if let Some(checkpoints) = checkpoints.as_mut() {
+                if let Err(err) = checkpoints.update(&label, progress.searched_to) {
+                    eprintln!("Checkpoint Write Error {}", err);
+                }
+            }
+        },
+    )
 }
 
 fn main() {
     let input = "iwrupvqb";
-    let part_one_soln = find_valid_checksum(input, "00000", 0, 1000000).unwrap();
-    println!("Part One: {}", part_one_soln);
-    let (tx, rx) = mpsc::channel();
-    for val in 0..10 {
-        let tx1 = tx.clone();
-        thread::spawn(move || {
-            tx1.send(find_valid_checksum(
-                input,
-                "000000",
-                part_one_soln + 1000000 * val,
-                part_one_soln + 1000000 * (val + 1) - 1,
-            ))
-            .unwrap();
-        });
+    let args: Vec<String> = env::args().skip(1).collect();
+    let algorithm = match args.iter().position(|x| x == "--hash") {
+        Some(n) => args
+            .get(n + 1)
+            .ok_or_else(|| String::from("--hash requires an algorithm"))
+            .and_then(|x| x.parse())
+            .unwrap_or_else(|err| {
+                eprintln!("Argument Error: {}", err);
+                process::exit(1);
+            }),
+        None => HashAlgorithm::Md5,
+    };
+    let threads = match args.iter().position(|x| x == "--threads") {
+        Some(n) => args
+            .get(n + 1)
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_else(|| {
+                eprintln!("Argument Error: --threads requires a number");
+                process::exit(1);
+            }),
+        None => default_threads(),
+    };
+
+    let checkpoints = args.iter().position(|x| x == "--checkpoint").map(|n| {
+        args.get(n + 1)
+            .ok_or_else(|| String::from("--checkpoint requires a path"))
+            .and_then(Checkpoints::load)
+            .unwrap_or_else(|err| {
+                eprintln!("Checkpoint Error: {}", err);
+                process::exit(1);
+            })
+    });
+    let resume = args.iter().any(|x| x == "--resume");
+    if resume && checkpoints.is_none() {
+        eprintln!("Argument Error: --resume requires --checkpoint");
+        process::exit(1);
+    }
+    let mut options = SearchOptions {
+        algorithm,
+        threads,
+        checkpoints,
+        resume,
+    };
+
+    let mut sh = algorithm.hasher(); // only used to show the winning hashes
+
+    let difficulty = args.iter().position(|x| x == "--difficulty").map(|n| {
+        args.get(n + 1)
+            .ok_or_else(|| String::from("--difficulty requires a difficulty"))
+            .and_then(|x| x.parse::<Difficulty>())
+            .unwrap_or_else(|err| {
+                eprintln!("Argument Error: {}", err);
+                process::exit(1);
+            })
+    });
+    let five_zeros = Difficulty::hex_prefix("00000").unwrap();
+    let six_zeros = Difficulty::hex_prefix("000000").unwrap();
+
+    // list every nonce below a limit instead of just the lowest, e.g. --all 10000000
+    if let Some(n) = args.iter().position(|x| x == "--all") {
+        let end_index: u64 = args
+            .get(n + 1)
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_else(|| {
+                eprintln!("Argument Error: --all requires a number");
+                process::exit(1);
+            });
+        let difficulty = difficulty.unwrap_or(five_zeros);
+        let mut count = 0;
+        for found in fast::find_all(algorithm, input, &difficulty, 0, end_index) {
+            println!("{} ({})", found.nonce, found.hex());
+            count += 1;
+        }
+        println!("{} nonces below {} meet {}", count, end_index, difficulty);
+        return;
     }
-    drop(tx);
 
-    for received in rx {
-        if let Some(x) = received {
-            println!("Part Two: {}", x)
+    // a custom difficulty replaces the puzzle parts, e.g. --difficulty bits:24
+    if let Some(difficulty) = difficulty {
+        match search(&mut options, input, &difficulty, 0) {
+            Some(x) => {
+                let hash = sh.hex_digest(&format!("{}{}", input, x));
+                println!("Lowest Nonce: {} ({})", x, hash);
+            }
+            None => println!("No nonce can meet {}", difficulty),
         }
+        return;
     }
+
+    let part_one_soln = search(&mut options, input, &five_zeros, 0).unwrap();
+    let part_one_hash = sh.hex_digest(&format!("{}{}", input, part_one_soln));
+    println!("Part One: {} ({})", part_one_soln, part_one_hash);
+    // any hash with six leading zeros also has five, so there's no need to look below part one
+    let part_two_soln = search(&mut options, input, &six_zeros, part_one_soln).unwrap();
+    let part_two_hash = sh.hex_digest(&format!("{}{}", input, part_two_soln));
+    println!("Part Two: {} ({})", part_two_soln, part_two_hash);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn key(prefix: &str) -> Difficulty {
+        Difficulty::hex_prefix(prefix).unwrap()
+    }
+
     #[test]
     fn test_valid_checksum() {
         assert_eq!(
-            find_valid_checksum("abcdef", "00000", 609043, 1000000).unwrap(),
+            find_valid_checksum(HashAlgorithm::Md5, "abcdef", &key("00000"), 609043, 1000000)
+                .unwrap(),
             609043
         );
     }
 
+    #[test]
+    fn test_other_algorithms() {
+        assert_eq!(
+            find_valid_checksum(HashAlgorithm::Sha1, "abcdef", &key("000"), 0, 100000),
+            Some(3693)
+        );
+        assert_eq!(
+            find_valid_checksum(HashAlgorithm::Sha256, "abcdef", &key("000"), 0, 100000),
+            Some(7099)
+        );
+    }
+
+    #[test]
+    fn test_difficulty_kinds() {
+        // five zero nibbles are exactly twenty zero bits
+        assert_eq!(
+            find_valid_checksum(
+                HashAlgorithm::Md5,
+                "abcdef",
+                &Difficulty::LeadingZeroBits(20),
+                0,
+                1000000
+            ),
+            Some(609043)
+        );
+        let below: Difficulty = "below:00001".parse().unwrap();
+        assert_eq!(
+            find_valid_checksum(HashAlgorithm::Md5, "abcdef", &below, 0, 1000000),
+            Some(609043)
+        );
+        let suffix: Difficulty = "suffix:000".parse().unwrap();
+        let x = find_valid_checksum(HashAlgorithm::Md5, "abcdef", &suffix, 0, 100000).unwrap();
+        let hash = HashAlgorithm::Md5
+            .hasher()
+            .hex_digest(&format!("abcdef{}", x));
+        assert!(hash.ends_with("000"));
+    }
+
     // #[test]
     // fn test_part_one(){
     //     assert_eq!(part_one("abcdef"),609043);
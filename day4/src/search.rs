@@ -0,0 +1,249 @@
+use crate::difficulty::Difficulty;
+use crate::find_valid_checksum;
+use crate::hasher::HashAlgorithm;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// nonces handed to a worker at a time, small enough that workers stop soon after a match
+const CHUNK_SIZE: u64 = 4096;
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProgressReport {
+    pub searched_to: u64,
+    pub hashes_per_second: f64,
+    pub finished: bool,
+}
+
+struct Watermark {
+    searched_to: u64,
+    finished: BTreeMap<u64, u64>,
+    hashes: u64,
+}
+
+impl Watermark {
+    fn complete(&mut self, chunk_start: u64, chunk_end: u64) {
+        // chunks finish out of order, the watermark only moves over a gapless run of them
+        self.hashes += chunk_end - chunk_start;
+        self.finished.insert(chunk_start, chunk_end);
+        while let Some(end) = self.finished.remove(&self.searched_to) {
+            self.searched_to = end;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn parallel_search_with_progress(
+    algorithm: HashAlgorithm,
+    base: &str,
+    difficulty: &Difficulty,
+    starting_index: u64,
+    end_index: u64,
+    threads: usize,
+    interval: Duration,
+    report: &mut (dyn FnMut(ProgressReport) + Send),
+) -> Option<u64> {
+    // without this every chunk up to the end index would be handed out and rejected
+    if !difficulty.is_possible(algorithm.output_bytes()) {
+        return None;
+    }
+
+    // workers pull chunks in increasing order from a shared counter, so once a chunk starts past
+    // the best match every lower chunk has already been handed out and will finish searching
+    let next_chunk = AtomicU64::new(starting_index);
+    let best = AtomicU64::new(u64::MAX);
+    // every nonce below searched_to has been checked
+    let watermark = Mutex::new(Watermark {
+        searched_to: starting_index,
+        finished: BTreeMap::new(),
+        hashes: 0,
+    });
+    let snapshot = || {
+        let watermark = watermark.lock().unwrap();
+        (watermark.searched_to, watermark.hashes)
+    };
+
+    thread::scope(|scope| {
+        // the reporter wakes up every interval until all the workers have dropped their senders
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        for _ in 0..threads.max(1) {
+            let done_tx = done_tx.clone();
+            let (next_chunk, best, watermark) = (&next_chunk, &best, &watermark);
+            scope.spawn(move || {
+                let _done_tx = done_tx;
+                loop {
+                    let chunk_start = next_chunk.fetch_add(CHUNK_SIZE, Ordering::Relaxed);
+                    let chunk_end = chunk_start
+                        .saturating_add(CHUNK_SIZE)
+                        .min(end_index)
+                        .min(best.load(Ordering::Relaxed));
+                    if chunk_start >= chunk_end {
+                        break;
+                    }
+                    let found =
+                        find_valid_checksum(algorithm, base, difficulty, chunk_start, chunk_end);
+                    // a match means only the nonces below it in this chunk were checked
+                    let mut watermark = watermark.lock().unwrap();
+                    watermark.complete(chunk_start, found.unwrap_or(chunk_end));
+                    if let Some(x) = found {
+                        best.fetch_min(x, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            });
+        }
+        drop(done_tx);
+
+        let (mut last_time, mut last_hashes) = (Instant::now(), 0);
+        while let Err(mpsc::RecvTimeoutError::Timeout) = done_rx.recv_timeout(interval) {
+            let (searched_to, hashes) = snapshot();
+            let elapsed = last_time.elapsed().as_secs_f64();
+            report(ProgressReport {
+                searched_to,
+                hashes_per_second: (hashes - last_hashes) as f64 / elapsed,
+                finished: false,
+            });
+            last_time = Instant::now();
+            last_hashes = hashes;
+        }
+    });
+
+    // one last report so callers always see where the search finished
+    let (searched_to, _) = snapshot();
+    report(ProgressReport {
+        searched_to,
+        hashes_per_second: 0.0,
+        finished: true,
+    });
+
+    match best.into_inner() {
+        u64::MAX => None,
+        x => Some(x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(prefix: &str) -> Difficulty {
+        Difficulty::hex_prefix(prefix).unwrap()
+    }
+
+    fn parallel_search(
+        algorithm: HashAlgorithm,
+        base: &str,
+        difficulty: &Difficulty,
+        starting_index: u64,
+        end_index: u64,
+        threads: usize,
+    ) -> Option<u64> {
+        parallel_search_with_progress(
+            algorithm,
+            base,
+            difficulty,
+            starting_index,
+            end_index,
+            threads,
+            Duration::MAX,
+            &mut |_| (),
+        )
+    }
+
+    #[test]
+    fn test_finds_lowest_nonce() {
+        // more workers than chunks before the answer, so later chunks may match first
+        for threads in [1, 4, 16].iter() {
+            assert_eq!(
+                parallel_search(
+                    HashAlgorithm::Md5,
+                    "abcdef",
+                    &key("000"),
+                    0,
+                    100000,
+                    *threads
+                ),
+                find_valid_checksum(HashAlgorithm::Md5, "abcdef", &key("000"), 0, 100000)
+            );
+            assert_eq!(
+                parallel_search(
+                    HashAlgorithm::Md5,
+                    "abcdef",
+                    &key("00000"),
+                    600000,
+                    700000,
+                    *threads
+                ),
+                Some(609043)
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(
+            parallel_search(HashAlgorithm::Md5, "abcdef", &key("00000"), 0, 20000, 4),
+            None
+        );
+        assert_eq!(
+            parallel_search(HashAlgorithm::Md5, "abcdef", &key("0"), 10, 10, 4),
+            None
+        );
+        let impossible = Difficulty::LeadingZeroBits(129);
+        assert_eq!(
+            parallel_search(HashAlgorithm::Md5, "abcdef", &impossible, 0, u64::MAX, 4),
+            None
+        );
+    }
+
+    #[test]
+    fn test_progress_reports() {
+        let mut reports = Vec::new();
+        let found = parallel_search_with_progress(
+            HashAlgorithm::Md5,
+            "abcdef",
+            &key("0000"),
+            0,
+            u64::MAX,
+            4,
+            Duration::from_millis(1),
+            &mut |progress| reports.push(progress),
+        );
+        assert_eq!(found, Some(31556));
+
+        // the watermark never moves backwards and stops at the match
+        let last = reports.pop().unwrap();
+        assert!(last.finished);
+        assert_eq!(last.searched_to, 31556);
+        assert!(reports
+            .iter()
+            .all(|x| !x.finished && x.searched_to <= 31556));
+        assert!(reports
+            .windows(2)
+            .all(|x| x[0].searched_to <= x[1].searched_to));
+    }
+
+    #[test]
+    fn test_watermark() {
+        let mut watermark = Watermark {
+            searched_to: 100,
+            finished: BTreeMap::new(),
+            hashes: 0,
+        };
+        watermark.complete(200, 300);
+        assert_eq!(watermark.searched_to, 100);
+        watermark.complete(100, 200);
+        assert_eq!(watermark.searched_to, 300);
+        // a chunk cut short by a match leaves a gap the watermark can't cross
+        watermark.complete(300, 350);
+        watermark.complete(400, 500);
+        assert_eq!(watermark.searched_to, 350);
+        assert_eq!(watermark.hashes, 350);
+    }
+}
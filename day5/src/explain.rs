@@ -0,0 +1,176 @@
+use crate::scanner::{scan_evidence, Evidence};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Explanation {
+    pub input: String,
+    pub vowels: Vec<(usize, char)>,
+    pub double_letter: Option<(usize, char)>,
+    pub forbidden: Vec<(usize, &'static str)>,
+    pub repeated_pair: Option<(usize, usize, String)>,
+    pub sandwich: Option<(usize, String)>,
+}
+
+impl Explanation {
+    pub fn is_nice(&self) -> bool {
+        self.vowels.len() >= 3 && self.double_letter.is_some() && self.forbidden.is_empty()
+    }
+
+    pub fn is_nicer(&self) -> bool {
+        self.repeated_pair.is_some() && self.sandwich.is_some()
+    }
+}
+
+pub fn explain(input: &str) -> Explanation {
+    // keeps the scanner's evidence, every vowel and forbidden pair but only the first of the rest,
+    // positions are byte offsets into the input
+    let mut explanation = Explanation {
+        input: input.to_string(),
+        ..Default::default()
+    };
+    scan_evidence(input, |evidence| match evidence {
+        Evidence::Vowel(n, c) => explanation.vowels.push((n, c)),
+        Evidence::DoubleLetter(n, c) => {
+            explanation.double_letter.get_or_insert((n, c));
+        }
+        Evidence::Forbidden(n, pair) => explanation.forbidden.push((n, pair)),
+        Evidence::RepeatedPair(first, second, pair) => {
+            let pair = pair.iter().collect();
+            explanation
+                .repeated_pair
+                .get_or_insert((first, second, pair));
+        }
+        Evidence::Sandwich(n, chars) => {
+            explanation
+                .sandwich
+                .get_or_insert((n, chars.iter().collect()));
+        }
+    });
+    explanation
+}
+
+fn verdict(nice: bool) -> &'static str {
+    if nice {
+        "nice"
+    } else {
+        "naughty"
+    }
+}
+
+fn mark(pass: bool) -> &'static str {
+    if pass {
+        "[pass]"
+    } else {
+        "[fail]"
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.input)?;
+
+        writeln!(f, "  part one: {}", verdict(self.is_nice()))?;
+        let vowels: Vec<String> = self
+            .vowels
+            .iter()
+            .map(|(n, c)| format!("{}@{}", c, n))
+            .collect();
+        writeln!(
+            f,
+            "    {} three vowels: found {} {}",
+            mark(self.vowels.len() >= 3),
+            self.vowels.len(),
+            vowels.join(" ")
+        )?;
+        match self.double_letter {
+            Some((n, c)) => writeln!(f, "    {} double letter: {}{}@{}", mark(true), c, c, n)?,
+            None => writeln!(f, "    {} double letter: none", mark(false))?,
+        }
+        let forbidden: Vec<String> = self
+            .forbidden
+            .iter()
+            .map(|(n, s)| format!("{}@{}", s, n))
+            .collect();
+        match forbidden.is_empty() {
+            true => writeln!(f, "    {} no forbidden substrings", mark(true))?,
+            false => writeln!(
+                f,
+                "    {} forbidden substrings: {}",
+                mark(false),
+                forbidden.join(" ")
+            )?,
+        }
+
+        writeln!(f, "  part two: {}", verdict(self.is_nicer()))?;
+        match &self.repeated_pair {
+            Some((first, second, pair)) => writeln!(
+                f,
+                "    {} repeated pair: {}@{} and {}@{}",
+                mark(true),
+                pair,
+                first,
+                pair,
+                second
+            )?,
+            None => writeln!(f, "    {} repeated pair: none", mark(false))?,
+        }
+        match &self.sandwich {
+            Some((n, s)) => write!(f, "    {} sandwich: {}@{}", mark(true), s, n),
+            None => write!(f, "    {} sandwich: none", mark(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::scan;
+
+    #[test]
+    fn test_evidence() {
+        let explanation = explain("haegwjzuvuyypxyu");
+        assert_eq!(
+            explanation.vowels,
+            vec![(1, 'a'), (2, 'e'), (7, 'u'), (9, 'u'), (15, 'u')]
+        );
+        assert_eq!(explanation.double_letter, Some((10, 'y')));
+        assert_eq!(explanation.forbidden, vec![(13, "xy")]);
+        assert!(!explanation.is_nice());
+
+        let explanation = explain("qjhvhtzxzqqjkmpb");
+        assert_eq!(explanation.repeated_pair, Some((0, 10, String::from("qj"))));
+        assert_eq!(explanation.sandwich, Some((2, String::from("hvh"))));
+        assert!(explanation.is_nicer());
+        assert_eq!(explain("aaa").repeated_pair, None);
+
+        // positions are bytes, é takes two
+        let explanation = explain("éaéaé");
+        assert_eq!(explanation.vowels, vec![(2, 'a'), (5, 'a')]);
+        assert_eq!(explanation.sandwich, Some((0, String::from("éaé"))));
+        assert_eq!(explanation.repeated_pair, Some((0, 3, String::from("éa"))));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            explain("jchzalrnumimnmhp").to_string(),
+            "jchzalrnumimnmhp
+  part one: naughty
+    [pass] three vowels: found 3 a@4 u@8 i@10
+    [fail] double letter: none
+    [pass] no forbidden substrings
+  part two: naughty
+    [fail] repeated pair: none
+    [pass] sandwich: mim@9"
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_scanner() {
+        for line in include_str!("../input.txt").lines() {
+            let explanation = explain(line);
+            assert_eq!(explanation.is_nice(), scan(line).is_nice(), "{}", line);
+            assert_eq!(explanation.is_nicer(), scan(line).is_nicer(), "{}", line);
+        }
+    }
+}
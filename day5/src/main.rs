@@ -1,28 +1,40 @@
-use fancy_regex::Regex;
-use lazy_static::lazy_static;
+mod explain;
+mod rules;
+mod scanner;
+mod stats;
+
+use rules::Rule;
+use std::env;
 use std::fs;
 use std::process;
+use std::thread;
 
 fn is_nice(input: &str) -> bool {
-    lazy_static! {
-        static ref THREE_VOWELS: Regex = Regex::new(r"(?:[aeiou].*?){3,}").unwrap();
-        static ref DOUBLE_LETTER: Regex = Regex::new(r"(.)\1").unwrap();
-        static ref ILLEGAL_SUBSTRINGS: Regex = Regex::new(r"^(?!.*(ab|cd|pq|xy)).*$").unwrap();
-    }
+    scanner::scan(input).is_nice()
+}
 
-    THREE_VOWELS.is_match(input).unwrap()
-        && DOUBLE_LETTER.is_match(input).unwrap()
-        && ILLEGAL_SUBSTRINGS.is_match(input).unwrap()
+fn is_nicer(input: &str) -> bool {
+    scanner::scan(input).is_nicer()
 }
 
 fn part_one(input: &str) -> u32 {
-    input
-        .split("\r\n")
-        .fold(0, |acc, item| match is_nice(item) {
-            true => acc + 1,
-            false => acc,
-        })
+    // lines() handles both \n and \r\n endings
+    input.lines().fold(0, |acc, item| match is_nice(item) {
+        true => acc + 1,
+        false => acc,
+    })
+}
+
+fn part_two(input: &str) -> u32 {
+    input.lines().fold(0, |acc, item| match is_nicer(item) {
+        true => acc + 1,
+        false => acc,
+    })
+}
+fn count_matching(input: &str, rule: &dyn Rule) -> u32 {
+    input.lines().filter(|x| rule.matches(x)).count() as u32
 }
+
 fn main() {
     let input = fs::read_to_string("day5/input.txt").unwrap_or_else(|err| {
         eprintln!("File Load Error {}", err);
@@ -30,6 +42,49 @@ fn main() {
     });
 
     println!("Part One Solution: {}", part_one(&input));
+    println!("Part Two Solution: {}", part_two(&input));
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|x| x == "--explain") {
+        for line in input.lines() {
+            println!("{}", explain::explain(line));
+        }
+    }
+
+    // write per-rule statistics as json, e.g. --stats day5/stats.json
+    if let Some(n) = args.iter().position(|x| x == "--stats") {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let report = stats::classify(&input, threads);
+        for (rule, failures) in report.rules.iter().zip(report.failures.iter()) {
+            println!("{} failed {} of {}", rule, failures, report.total);
+        }
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        let written = match args.get(n + 1) {
+            Some(path) => fs::write(path, json).map_err(|err| err.to_string()),
+            None => Err(String::from("--stats requires an output path")),
+        };
+        if let Err(err) = written {
+            eprintln!("Stats Write Error: {}", err);
+            process::exit(1);
+        }
+    }
+
+    // classify with a ruleset from a config file, e.g. --rules day5/rules/part_one.rules
+    if let Some(n) = args.iter().position(|x| x == "--rules") {
+        let rules = args
+            .get(n + 1)
+            .ok_or_else(|| String::from("--rules requires a path"))
+            .and_then(|path| fs::read_to_string(path).map_err(|err| err.to_string()))
+            .and_then(|config| rules::parse_rules(&config))
+            .unwrap_or_else(|err| {
+                eprintln!("Rule Load Error: {}", err);
+                process::exit(1);
+            });
+        println!(
+            "Custom Rules Solution: {}",
+            count_matching(&input, rules.as_ref())
+        );
+    }
 }
 
 #[cfg(test)]
@@ -48,4 +103,25 @@ mod tests {
         assert!(!is_nice("haegwjzuvuyypxyu"));
         assert!(!is_nice("dvszwmarrgswjxmb"));
     }
+
+    #[test]
+    fn test_nicer_strings() {
+        assert!(is_nicer("qjhvhtzxzqqjkmpb"));
+        assert!(is_nicer("xxyxx"));
+    }
+
+    #[test]
+    fn test_not_nicer_strings() {
+        assert!(!is_nicer("uurcxstgmygtbstg"));
+        assert!(!is_nicer("ieodomkazucvgmuy"));
+        // the pair can't overlap itself
+        assert!(!is_nicer("aaa"));
+    }
+
+    #[test]
+    fn test_parts() {
+        let input = "ugknbfddgicrmopn\njchzalrnumimnmhp\r\nqjhvhtzxzqqjkmpb\nxxyxx\n";
+        assert_eq!(part_one(input), 1);
+        assert_eq!(part_two(input), 2);
+    }
 }
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+pub trait Rule: Send + Sync {
+    fn matches(&self, input: &str) -> bool;
+}
+
+pub struct VowelCount(pub usize);
+
+impl Rule for VowelCount {
+    fn matches(&self, input: &str) -> bool {
+        input.chars().filter(|c| "aeiou".contains(*c)).count() >= self.0
+    }
+}
+
+pub struct DoubleLetter;
+
+impl Rule for DoubleLetter {
+    fn matches(&self, input: &str) -> bool {
+        let chars: Vec<char> = input.chars().collect();
+        chars.windows(2).any(|x| x[0] == x[1])
+    }
+}
+
+pub struct ForbiddenSubstrings(pub Vec<String>);
+
+impl Rule for ForbiddenSubstrings {
+    fn matches(&self, input: &str) -> bool {
+        !self.0.iter().any(|x| input.contains(x.as_str()))
+    }
+}
+
+pub struct RepeatedPair;
+
+impl Rule for RepeatedPair {
+    fn matches(&self, input: &str) -> bool {
+        // remember where each pair first started, a repeat has to begin past the end of it
+        let chars: Vec<char> = input.chars().collect();
+        let mut first_seen = HashMap::new();
+        chars.windows(2).enumerate().any(|(n, pair)| {
+            let first = *first_seen.entry((pair[0], pair[1])).or_insert(n);
+            n >= first + 2
+        })
+    }
+}
+
+pub struct Sandwich;
+
+impl Rule for Sandwich {
+    fn matches(&self, input: &str) -> bool {
+        let chars: Vec<char> = input.chars().collect();
+        chars.windows(3).any(|x| x[0] == x[2])
+    }
+}
+
+pub struct All(pub Vec<Box<dyn Rule>>);
+
+impl Rule for All {
+    fn matches(&self, input: &str) -> bool {
+        self.0.iter().all(|rule| rule.matches(input))
+    }
+}
+
+pub struct Any(pub Vec<Box<dyn Rule>>);
+
+impl Rule for Any {
+    fn matches(&self, input: &str) -> bool {
+        self.0.iter().any(|rule| rule.matches(input))
+    }
+}
+
+pub struct Not(pub Box<dyn Rule>);
+
+impl Rule for Not {
+    fn matches(&self, input: &str) -> bool {
+        !self.0.matches(input)
+    }
+}
+
+fn parse_atom(tokens: &[&str]) -> Result<Box<dyn Rule>, String> {
+    match tokens {
+        ["not", rest @ ..] => Ok(Box::new(Not(parse_atom(rest)?))),
+        ["vowels", n] => n
+            .parse()
+            .map(|n| Box::new(VowelCount(n)) as Box<dyn Rule>)
+            .map_err(|_| format!("could not parse vowel count from {}", n)),
+        ["double"] => Ok(Box::new(DoubleLetter)),
+        ["forbid", substrings @ ..] if !substrings.is_empty() => Ok(Box::new(ForbiddenSubstrings(
+            substrings.iter().map(|x| x.to_string()).collect(),
+        ))),
+        ["pair"] => Ok(Box::new(RepeatedPair)),
+        ["sandwich"] => Ok(Box::new(Sandwich)),
+        _ => Err(format!("unknown rule '{}'", tokens.join(" "))),
+    }
+}
+
+fn parse_all(tokens: &[&str]) -> Result<Box<dyn Rule>, String> {
+    let mut rules = tokens
+        .split(|&x| x == "and")
+        .map(parse_atom)
+        .collect::<Result<Vec<_>, _>>()?;
+    match rules.len() {
+        1 => Ok(rules.pop().unwrap()),
+        _ => Ok(Box::new(All(rules))),
+    }
+}
+
+fn parse_line(line: &str) -> Result<Box<dyn Rule>, String> {
+    // `or` binds loosest, then `and`, then `not`
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut rules = tokens
+        .split(|&x| x == "or")
+        .map(parse_all)
+        .collect::<Result<Vec<_>, _>>()?;
+    match rules.len() {
+        1 => Ok(rules.pop().unwrap()),
+        _ => Ok(Box::new(Any(rules))),
+    }
+}
+
+pub fn parse_rules(config: &str) -> Result<Box<dyn Rule>, String> {
+    // every line is a rule and a string has to pass all of them, # starts a comment
+    let rules = config
+        .lines()
+        .enumerate()
+        .map(|(n, line)| (n, line.split('#').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(n, line)| parse_line(line).map_err(|err| format!("line {}: {}", n + 1, err)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Box::new(All(rules)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_nice, is_nicer};
+
+    const EXAMPLES: [&str; 9] = [
+        "ugknbfddgicrmopn",
+        "aaa",
+        "jchzalrnumimnmhp",
+        "haegwjzuvuyypxyu",
+        "dvszwmarrgswjxmb",
+        "qjhvhtzxzqqjkmpb",
+        "xxyxx",
+        "uurcxstgmygtbstg",
+        "ieodomkazucvgmuy",
+    ];
+
+    #[test]
+    fn test_config_files() {
+        let part_one = parse_rules(include_str!("../rules/part_one.rules")).unwrap();
+        let part_two = parse_rules(include_str!("../rules/part_two.rules")).unwrap();
+        for example in EXAMPLES.iter() {
+            assert_eq!(part_one.matches(example), is_nice(example));
+            assert_eq!(part_two.matches(example), is_nicer(example));
+        }
+    }
+
+    #[test]
+    fn test_combinators() {
+        let rules = parse_rules("vowels 2 or sandwich and not double").unwrap();
+        assert!(rules.matches("ae"));
+        assert!(rules.matches("xyx"));
+        assert!(!rules.matches("xyxx"));
+        assert!(!rules.matches("a"));
+
+        // lines are combined with and
+        let rules = parse_rules("not forbid zz # must contain zz\nvowels 1").unwrap();
+        assert!(rules.matches("azz"));
+        assert!(!rules.matches("zz"));
+        assert!(!rules.matches("az"));
+    }
+
+    #[test]
+    fn test_config_errors() {
+        assert_eq!(
+            parse_rules("double\n\nvowels three").err(),
+            Some(String::from(
+                "line 3: could not parse vowel count from three"
+            ))
+        );
+        assert!(parse_rules("double and").is_err());
+        assert!(parse_rules("forbid").is_err());
+        assert!(parse_rules("palindrome").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+// pairs of lowercase letters get a fixed table, anything else falls back to a map
+const LETTER_PAIRS: usize = 26 * 26;
+const UNSEEN: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Scan {
+    pub vowels: usize,
+    pub double_letter: bool,
+    pub forbidden_substring: bool,
+    pub repeated_pair: bool,
+    pub sandwich: bool,
+}
+
+impl Scan {
+    pub fn is_nice(&self) -> bool {
+        self.vowels >= 3 && self.double_letter && !self.forbidden_substring
+    }
+
+    pub fn is_nicer(&self) -> bool {
+        self.repeated_pair && self.sandwich
+    }
+}
+
+// what the scanner saw that satisfies or breaks a rule, positions are byte offsets into the input
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Evidence {
+    Vowel(usize, char),
+    DoubleLetter(usize, char),
+    Forbidden(usize, &'static str),
+    // where the first copy of the pair starts and where the repeat starts
+    RepeatedPair(usize, usize, [char; 2]),
+    Sandwich(usize, [char; 3]),
+}
+
+fn letter_pair_index(first: char, second: char) -> Option<usize> {
+    if first.is_ascii_lowercase() && second.is_ascii_lowercase() {
+        Some((first as u8 - b'a') as usize * 26 + (second as u8 - b'a') as usize)
+    } else {
+        None
+    }
+}
+
+fn forbidden(first: char, second: char) -> Option<&'static str> {
+    match (first, second) {
+        ('a', 'b') => Some("ab"),
+        ('c', 'd') => Some("cd"),
+        ('p', 'q') => Some("pq"),
+        ('x', 'y') => Some("xy"),
+        _ => None,
+    }
+}
+
+pub fn scan_evidence(input: &str, mut found: impl FnMut(Evidence)) {
+    // walks the characters once, only ever looking back at the previous two. pair overlaps are
+    // decided by character counts so a multi-byte character is a single letter like it is for
+    // the rules. every vowel, double letter, forbidden pair and sandwich is reported but only the
+    // first repeated pair
+    let mut first_pair_at = [(UNSEEN, 0); LETTER_PAIRS];
+    let mut other_pairs: HashMap<(char, char), (usize, usize)> = HashMap::new();
+    let mut repeated_pair = false;
+    // the previous two characters with their byte offsets
+    let mut before_last: Option<(usize, char)> = None;
+    let mut last: Option<(usize, char)> = None;
+
+    for (n, (position, c)) in input.char_indices().enumerate() {
+        if matches!(c, 'a' | 'e' | 'i' | 'o' | 'u') {
+            found(Evidence::Vowel(position, c));
+        }
+
+        if let Some((previous_at, previous)) = last {
+            if previous == c {
+                found(Evidence::DoubleLetter(previous_at, c));
+            }
+            if let Some(pair) = forbidden(previous, c) {
+                found(Evidence::Forbidden(previous_at, pair));
+            }
+
+            // a pair starting at n - 1 only counts as repeated if its first copy ended before it
+            if !repeated_pair {
+                let start = (n - 1, previous_at);
+                let first = match letter_pair_index(previous, c) {
+                    Some(index) => {
+                        if first_pair_at[index].0 == UNSEEN {
+                            first_pair_at[index] = start;
+                        }
+                        first_pair_at[index]
+                    }
+                    None => *other_pairs.entry((previous, c)).or_insert(start),
+                };
+                if start.0 >= first.0 + 2 {
+                    repeated_pair = true;
+                    found(Evidence::RepeatedPair(first.1, previous_at, [previous, c]));
+                }
+            }
+        }
+        if let (Some((first_at, first)), Some((_, middle))) = (before_last, last) {
+            if first == c {
+                found(Evidence::Sandwich(first_at, [first, middle, c]));
+            }
+        }
+
+        before_last = last;
+        last = Some((position, c));
+    }
+}
+
+pub fn scan(input: &str) -> Scan {
+    let mut result = Scan::default();
+    scan_evidence(input, |evidence| match evidence {
+        Evidence::Vowel(..) => result.vowels += 1,
+        Evidence::DoubleLetter(..) => result.double_letter = true,
+        Evidence::Forbidden(..) => result.forbidden_substring = true,
+        Evidence::RepeatedPair(..) => result.repeated_pair = true,
+        Evidence::Sandwich(..) => result.sandwich = true,
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fancy_regex::Regex;
+    use lazy_static::lazy_static;
+
+    // the original regex implementations, kept as the reference for the scanner
+    fn regex_is_nice(input: &str) -> bool {
+        lazy_static! {
+            static ref THREE_VOWELS: Regex = Regex::new(r"(?:[aeiou].*?){3,}").unwrap();
+            static ref DOUBLE_LETTER: Regex = Regex::new(r"(.)\1").unwrap();
+            static ref ILLEGAL_SUBSTRINGS: Regex = Regex::new(r"^(?!.*(ab|cd|pq|xy)).*$").unwrap();
+        }
+
+        THREE_VOWELS.is_match(input).unwrap()
+            && DOUBLE_LETTER.is_match(input).unwrap()
+            && ILLEGAL_SUBSTRINGS.is_match(input).unwrap()
+    }
+
+    fn regex_is_nicer(input: &str) -> bool {
+        lazy_static! {
+            static ref REPEATED_PAIR: Regex = Regex::new(r"(..).*\1").unwrap();
+            static ref SANDWICH: Regex = Regex::new(r"(.).\1").unwrap();
+        }
+
+        REPEATED_PAIR.is_match(input).unwrap() && SANDWICH.is_match(input).unwrap()
+    }
+
+    fn random_strings(count: usize, seed: u64) -> Vec<String> {
+        // a small alphabet so every rule passes and fails often, plus some non-letters and
+        // characters longer than a byte
+        const ALPHABET: &[char] = &[
+            'a', 'a', 'e', 'i', 'o', 'u', 'b', 'c', 'd', 'p', 'q', 'x', 'y', 'z', 'Z', '-', ' ',
+            'é', 'é', 'ü', '✓',
+        ];
+        // a small linear congruential generator so the tests don't need a rand dependency
+        let mut state = seed;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as usize
+        };
+        (0..count)
+            .map(|_| {
+                let length = next() % 20;
+                (0..length)
+                    .map(|_| ALPHABET[next() % ALPHABET.len()])
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan() {
+        let result = scan("aaxyzaba");
+        assert_eq!(result.vowels, 4);
+        assert!(result.double_letter);
+        assert!(result.forbidden_substring);
+        assert!(!result.repeated_pair);
+        assert!(result.sandwich);
+        assert_eq!(scan(""), Scan::default());
+        assert!(scan("Z-Z-").repeated_pair);
+        // é is two bytes, compared as one character
+        assert!(scan("aeiéé").is_nice());
+        assert!(!scan("éé").sandwich);
+        assert!(scan("éaé").sandwich);
+        assert!(!scan("ééé").repeated_pair);
+        assert!(scan("éüéü").repeated_pair);
+    }
+
+    #[test]
+    fn test_matches_regex() {
+        for input in random_strings(20000, 5) {
+            let result = scan(&input);
+            assert_eq!(result.is_nice(), regex_is_nice(&input), "{:?}", input);
+            assert_eq!(result.is_nicer(), regex_is_nicer(&input), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_input_matches_regex() {
+        let input = include_str!("../input.txt");
+        for line in input.lines() {
+            assert_eq!(scan(line).is_nice(), regex_is_nice(line), "{}", line);
+            assert_eq!(scan(line).is_nicer(), regex_is_nicer(line), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::scanner::{scan, Scan};
+use serde::Serialize;
+use std::thread;
+
+pub const RULES: [&str; 5] = [
+    "three vowels",
+    "double letter",
+    "no forbidden substrings",
+    "repeated pair",
+    "sandwich",
+];
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct Report {
+    pub rules: Vec<&'static str>,
+    pub total: usize,
+    pub failures: Vec<usize>,
+    pub co_failures: Vec<Vec<usize>>,
+    pub nice: Vec<String>,
+    pub nicer: Vec<String>,
+}
+
+fn failed_rules(scan: &Scan) -> [bool; 5] {
+    // in the same order as RULES
+    [
+        scan.vowels < 3,
+        !scan.double_letter,
+        scan.forbidden_substring,
+        !scan.repeated_pair,
+        !scan.sandwich,
+    ]
+}
+
+impl Report {
+    fn new() -> Self {
+        Self {
+            rules: RULES.to_vec(),
+            failures: vec![0; RULES.len()],
+            co_failures: vec![vec![0; RULES.len()]; RULES.len()],
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, line: &str) {
+        let scan = scan(line);
+        let failed = failed_rules(&scan);
+        self.total += 1;
+        for (i, _) in failed.iter().enumerate().filter(|(_, &x)| x) {
+            self.failures[i] += 1;
+            for (j, _) in failed.iter().enumerate().filter(|(_, &x)| x) {
+                self.co_failures[i][j] += 1;
+            }
+        }
+        if scan.is_nice() {
+            self.nice.push(line.to_string());
+        }
+        if scan.is_nicer() {
+            self.nicer.push(line.to_string());
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        // other has to come from lines after self's so the string lists stay in input order
+        self.total += other.total;
+        for i in 0..RULES.len() {
+            self.failures[i] += other.failures[i];
+            for j in 0..RULES.len() {
+                self.co_failures[i][j] += other.co_failures[i][j];
+            }
+        }
+        self.nice.extend(other.nice);
+        self.nicer.extend(other.nicer);
+        self
+    }
+}
+
+pub fn classify(input: &str, threads: usize) -> Report {
+    // each thread builds a report for a contiguous block of lines, merged back in order
+    let lines: Vec<&str> = input.lines().collect();
+    let chunk_size = lines.len().div_ceil(threads.max(1)).max(1);
+    thread::scope(|scope| {
+        let workers: Vec<_> = lines
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut report = Report::new();
+                    for line in chunk {
+                        report.add(line);
+                    }
+                    report
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .fold(Report::new(), Report::merge)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: &str = "ugknbfddgicrmopn\naaa\njchzalrnumimnmhp\nhaegwjzuvuyypxyu
+dvszwmarrgswjxmb\nqjhvhtzxzqqjkmpb\nxxyxx\nuurcxstgmygtbstg\nieodomkazucvgmuy";
+
+    #[test]
+    fn test_report() {
+        let report = classify(EXAMPLES, 1);
+        assert_eq!(report.total, 9);
+        assert_eq!(report.nice, vec!["ugknbfddgicrmopn", "aaa"]);
+        assert_eq!(report.nicer, vec!["qjhvhtzxzqqjkmpb", "xxyxx"]);
+        assert_eq!(report.failures, vec![4, 2, 2, 6, 3]);
+        // the diagonal is the failure count for each rule on its own
+        for i in 0..RULES.len() {
+            assert_eq!(report.co_failures[i][i], report.failures[i]);
+        }
+        // both strings with a forbidden substring also have a double letter
+        assert_eq!(report.co_failures[1][2], 0);
+        assert_eq!(report.co_failures[0][4], 2);
+    }
+
+    #[test]
+    fn test_threads_agree() {
+        let input = include_str!("../input.txt");
+        let single = classify(input, 1);
+        assert_eq!(single.nice.len(), 258);
+        assert_eq!(single.nicer.len(), 53);
+        for threads in [2, 3, 7, 64, 2000].iter() {
+            assert_eq!(classify(input, *threads), single);
+        }
+        assert_eq!(classify("", 4).total, 0);
+    }
+
+    #[test]
+    fn test_json() {
+        let json = serde_json::to_value(classify("aaa", 2)).unwrap();
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["nice"][0], "aaa");
+        assert_eq!(json["rules"][3], "repeated pair");
+        assert_eq!(json["co_failures"][3][4], 0);
+    }
+}
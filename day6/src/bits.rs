@@ -0,0 +1,150 @@
+use crate::{GridCommand, GridInstruction, LightGrid};
+
+const WORD_BITS: usize = 64;
+
+// one bit per light, each row starts on a fresh word so a rectangle is a run of words per row
+pub struct BitGrid {
+    words: Vec<u64>,
+    rows: usize,
+    columns: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let words_per_row = columns.div_ceil(WORD_BITS);
+        Self {
+            words: vec![0; rows * words_per_row],
+            rows,
+            columns,
+            words_per_row,
+        }
+    }
+}
+
+fn masks(c0: usize, c1: usize) -> impl Iterator<Item = (usize, u64)> {
+    // the word index within a row and the bits of columns c0..=c1 that fall in it
+    (c0 / WORD_BITS..=c1 / WORD_BITS).map(move |word| {
+        let first = c0.max(word * WORD_BITS) % WORD_BITS;
+        let last = c1.min(word * WORD_BITS + WORD_BITS - 1) % WORD_BITS;
+        (word, (!0u64 >> (WORD_BITS - 1 - last)) & (!0u64 << first))
+    })
+}
+
+impl LightGrid for BitGrid {
+    fn count(&self) -> u64 {
+        self.words.iter().map(|x| x.count_ones() as u64).sum()
+    }
+
+    fn execute(&mut self, instruction: &GridInstruction) {
+        let (r0, c0) = instruction.start_pos;
+        let (r1, c1) = instruction.end_pos;
+        // reversed rectangles cover nothing, the same as on the dense grid
+        if r0 > r1 || c0 > c1 {
+            return;
+        }
+        if r1 >= self.rows || c1 >= self.columns {
+            panic!("Index Out of Bounds Error");
+        }
+        // the same words are touched in every row, so the masks are only worked out once
+        let masks: Vec<(usize, u64)> = masks(c0, c1).collect();
+        let rows = self.words[r0 * self.words_per_row..(r1 + 1) * self.words_per_row]
+            .chunks_exact_mut(self.words_per_row);
+        match instruction.command {
+            GridCommand::On => apply(rows, &masks, |word, mask| *word |= mask),
+            GridCommand::Off => apply(rows, &masks, |word, mask| *word &= !mask),
+            GridCommand::Toggle => apply(rows, &masks, |word, mask| *word ^= mask),
+        }
+    }
+}
+
+fn apply<'a>(
+    rows: impl Iterator<Item = &'a mut [u64]>,
+    masks: &[(usize, u64)],
+    operation: impl Fn(&mut u64, u64),
+) {
+    for row in rows {
+        for &(word, mask) in masks.iter() {
+            operation(&mut row[word], mask);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::random_instructions;
+    use crate::{parse, Light, StaticGrid};
+    use std::time::Instant;
+
+    fn light_at(grid: &BitGrid, (r, c): (usize, usize)) -> Light {
+        match grid.words[r * grid.words_per_row + c / WORD_BITS] >> (c % WORD_BITS) & 1 {
+            1 => Light::On,
+            _ => Light::Off,
+        }
+    }
+
+    #[test]
+    fn test_masks() {
+        assert_eq!(masks(0, 0).collect::<Vec<_>>(), vec![(0, 1)]);
+        assert_eq!(masks(3, 5).collect::<Vec<_>>(), vec![(0, 0b111000)]);
+        assert_eq!(
+            masks(60, 130).collect::<Vec<_>>(),
+            vec![(0, 0xf << 60), (1, !0), (2, 0b111)]
+        );
+        assert_eq!(masks(64, 127).collect::<Vec<_>>(), vec![(1, !0)]);
+    }
+
+    #[test]
+    fn test_matches_dense() {
+        // 70 columns so rectangles cross a word boundary and leave padding bits in the last word
+        for seed in 0..20 {
+            let mut dense = StaticGrid::new(70, 70);
+            let mut bits = BitGrid::new(70, 70);
+            for instruction in random_instructions(50, 70, seed) {
+                dense.execute(&instruction);
+                bits.execute(&instruction);
+                assert_eq!(bits.count(), dense.count());
+            }
+            for row in 0..70 {
+                for col in 0..70 {
+                    assert_eq!(light_at(&bits, (row, col)), dense[(row, col)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_input() {
+        let mut grid = BitGrid::new(1000, 1000);
+        for instruction in parse(include_str!("../input.txt")).unwrap().iter() {
+            grid.execute(instruction);
+        }
+        assert_eq!(grid.count(), 543903);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_against_dense() {
+        // run with `cargo test --release -p day6 -- --ignored --nocapture`
+        let instructions = parse(include_str!("../input.txt")).unwrap();
+
+        let start = Instant::now();
+        let mut dense = StaticGrid::new(1000, 1000);
+        for instruction in instructions.iter() {
+            dense.execute(instruction);
+        }
+        let dense_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut bits = BitGrid::new(1000, 1000);
+        for instruction in instructions.iter() {
+            bits.execute(instruction);
+        }
+        let bits_time = start.elapsed();
+
+        assert_eq!(dense.count(), bits.count());
+        println!("Grid<Light>: {:?}", dense_time);
+        println!("BitGrid:     {:?}", bits_time);
+    }
+}
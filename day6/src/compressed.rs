@@ -0,0 +1,166 @@
+use crate::{GridInstruction, Lamp, LightGrid};
+
+// the grid is cut into bands of rows and columns wherever an instruction starts or ends, so every
+// light in the block where a row band meets a column band is always in the same state
+pub struct CompressedGrid<T> {
+    rows: usize,
+    columns: usize,
+    row_cuts: Vec<usize>,
+    column_cuts: Vec<usize>,
+    blocks: Vec<Vec<T>>,
+}
+
+impl<T: Lamp> CompressedGrid<T> {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self::filled(rows, columns, T::default())
+    }
+
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self {
+        Self {
+            rows,
+            columns,
+            row_cuts: vec![0],
+            column_cuts: vec![0],
+            blocks: vec![vec![value]],
+        }
+    }
+
+    pub fn update(&mut self, instruction: &GridInstruction, mut f: impl FnMut(&mut T)) {
+        // runs f once on every block inside the instruction's rectangle
+        let (r0, c0) = instruction.start_pos;
+        let (r1, c1) = instruction.end_pos;
+        // reversed rectangles cover nothing, the same as on the dense grid
+        if r0 > r1 || c0 > c1 {
+            return;
+        }
+        if r1 >= self.rows || c1 >= self.columns {
+            panic!("Index Out of Bounds Error");
+        }
+        let (first_row, last_row) = (self.split_rows(r0), self.split_rows(r1 + 1));
+        let (first_column, last_column) = (self.split_columns(c0), self.split_columns(c1 + 1));
+        for row in self.blocks[first_row..last_row].iter_mut() {
+            for block in row[first_column..last_column].iter_mut() {
+                f(block);
+            }
+        }
+    }
+
+    fn split_rows(&mut self, at: usize) -> usize {
+        // returns the index of the band starting at the given row, a new band copies the one it
+        // was cut from
+        if at == self.rows {
+            return self.row_cuts.len();
+        }
+        match self.row_cuts.binary_search(&at) {
+            Ok(n) => n,
+            Err(n) => {
+                self.row_cuts.insert(n, at);
+                let copy = self.blocks[n - 1].clone();
+                self.blocks.insert(n, copy);
+                n
+            }
+        }
+    }
+
+    fn split_columns(&mut self, at: usize) -> usize {
+        if at == self.columns {
+            return self.column_cuts.len();
+        }
+        match self.column_cuts.binary_search(&at) {
+            Ok(n) => n,
+            Err(n) => {
+                self.column_cuts.insert(n, at);
+                for row in self.blocks.iter_mut() {
+                    let copy = row[n - 1].clone();
+                    row.insert(n, copy);
+                }
+                n
+            }
+        }
+    }
+}
+
+fn band_sizes(cuts: &[usize], end: usize) -> impl Iterator<Item = u64> + '_ {
+    cuts.iter()
+        .zip(cuts.iter().skip(1).copied().chain(std::iter::once(end)))
+        .map(|(start, end)| (end - start) as u64)
+}
+
+impl<T: Lamp> LightGrid for CompressedGrid<T> {
+    fn count(&self) -> u64 {
+        band_sizes(&self.row_cuts, self.rows)
+            .zip(self.blocks.iter())
+            .map(|(height, row)| {
+                band_sizes(&self.column_cuts, self.columns)
+                    .zip(row.iter())
+                    .map(|(width, block)| block.brightness() as u64 * width)
+                    .sum::<u64>()
+                    * height
+            })
+            .sum()
+    }
+
+    fn execute(&mut self, instruction: &GridInstruction) {
+        self.update(instruction, |block| block.apply(instruction.command));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::random_instructions;
+    use crate::{parse, DynamicGrid, GridCommand, Light, StaticGrid};
+
+    #[test]
+    fn test_matches_dense() {
+        for seed in 0..20 {
+            let mut dense_static = StaticGrid::new(30, 30);
+            let mut dense_dynamic = DynamicGrid::new(30, 30);
+            let mut compressed_static = CompressedGrid::<Light>::new(30, 30);
+            let mut compressed_dynamic = CompressedGrid::<u32>::new(30, 30);
+            for instruction in random_instructions(50, 30, seed) {
+                dense_static.execute(&instruction);
+                dense_dynamic.execute(&instruction);
+                compressed_static.execute(&instruction);
+                compressed_dynamic.execute(&instruction);
+                assert_eq!(compressed_static.count(), dense_static.count());
+                assert_eq!(compressed_dynamic.count(), dense_dynamic.count());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cuts() {
+        let mut grid = CompressedGrid::<Light>::new(1000, 1000);
+        grid.execute(&GridInstruction::new(GridCommand::On, (0, 0), (999, 999)));
+        assert_eq!(grid.count(), 1000000);
+        // a rectangle touching the edges never needs a band past them
+        assert_eq!(grid.blocks.len(), 1);
+        grid.execute(&GridInstruction::new(GridCommand::Toggle, (0, 0), (999, 0)));
+        assert_eq!(grid.count(), 999000);
+        grid.execute(&GridInstruction::new(
+            GridCommand::Off,
+            (499, 499),
+            (500, 500),
+        ));
+        assert_eq!(grid.count(), 998996);
+        assert_eq!(grid.row_cuts, vec![0, 499, 501]);
+        assert_eq!(grid.column_cuts, vec![0, 1, 499, 501]);
+        // reversed rectangles change nothing
+        grid.execute(&GridInstruction::new(GridCommand::Off, (10, 10), (5, 5)));
+        assert_eq!(grid.count(), 998996);
+    }
+
+    #[test]
+    fn test_input() {
+        let instructions = parse(include_str!("../input.txt")).unwrap();
+        let mut static_grid = CompressedGrid::<Light>::new(1000, 1000);
+        let mut dynamic_grid = CompressedGrid::<u32>::new(1000, 1000);
+        for instruction in instructions.iter() {
+            static_grid.execute(instruction);
+            dynamic_grid.execute(instruction);
+        }
+        assert_eq!(static_grid.count(), 543903);
+        assert_eq!(dynamic_grid.count(), 14687245);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Grid, Lamp, Light, StaticGrid};
+use grid::image::shade;
+
+const PGM_MAX: u64 = 255;
+
+fn max_brightness<T: Lamp>(grid: &Grid<T>) -> u64 {
+    grid.iter()
+        .map(|x| x.brightness() as u64)
+        .max()
+        .unwrap_or(0)
+        .max(1)
+}
+
+pub fn to_pbm(grid: &StaticGrid) -> String {
+    // writes a plain (P1) bitmap, pbm draws 1 as black so lit lights are written as 0
+    let mut image = format!("P1\n{} {}\n", grid.columns(), grid.rows());
+    for row in grid.row_slices() {
+        let line: Vec<&str> = row
+            .iter()
+            .map(|x| if *x == Light::On { "0" } else { "1" })
+            .collect();
+        image.push_str(&line.join(" "));
+        image.push('\n');
+    }
+    image
+}
+
+pub fn to_pgm<T: Lamp>(grid: &Grid<T>) -> String {
+    // writes a plain (P2) greyscale image where the brightest light in the grid is white
+    let max_value = max_brightness(grid);
+    let scaled = grid.map(|x| (x.brightness() as u64 * PGM_MAX / max_value) as u32);
+    grid::image::to_pgm(&scaled, PGM_MAX as u32)
+}
+
+pub fn to_ascii<T: Lamp>(grid: &Grid<T>, width: usize, height: usize) -> String {
+    // shrinks the grid to at most width x height characters, each one showing the average
+    // brightness of the block of lights it covers
+    let (width, height) = (width.min(grid.columns()), height.min(grid.rows()));
+    let max_value = max_brightness(grid);
+
+    let mut preview = String::with_capacity((width + 1) * height);
+    for block_row in 0..height {
+        let rows = block_row * grid.rows() / height..(block_row + 1) * grid.rows() / height;
+        for block_column in 0..width {
+            let columns =
+                block_column * grid.columns() / width..(block_column + 1) * grid.columns() / width;
+            let lights = (rows.len() * columns.len()) as u64;
+            let total: u64 = rows
+                .clone()
+                .flat_map(|row| columns.clone().map(move |col| (row, col)))
+                .map(|position| grid[position].brightness() as u64)
+                .sum();
+            preview.push(shade(total, lights * max_value));
+        }
+        preview.push('\n');
+    }
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DynamicGrid, GridCommand, GridInstruction, LightGrid};
+
+    #[test]
+    fn test_pbm_export() {
+        let mut grid = StaticGrid::new(2, 3);
+        grid.execute(&GridInstruction::new(GridCommand::On, (0, 1), (1, 1)));
+        assert_eq!(to_pbm(&grid), "P1\n3 2\n1 0 1\n1 0 1\n");
+    }
+
+    #[test]
+    fn test_pgm_export() {
+        let mut grid = DynamicGrid::new(2, 2);
+        grid.execute(&GridInstruction::new(GridCommand::On, (0, 0), (1, 0)));
+        grid.execute(&GridInstruction::new(GridCommand::Toggle, (0, 0), (0, 0)));
+        assert_eq!(to_pgm(&grid), "P2\n2 2\n255\n255 0\n85 0\n");
+        // an unlit grid is all black rather than dividing by zero
+        assert_eq!(to_pgm(&DynamicGrid::new(1, 2)), "P2\n2 1\n255\n0 0\n");
+    }
+
+    #[test]
+    fn test_ascii_preview() {
+        let mut grid = StaticGrid::new(4, 8);
+        grid.execute(&GridInstruction::new(GridCommand::On, (0, 0), (1, 3)));
+        grid.execute(&GridInstruction::new(GridCommand::On, (2, 4), (2, 4)));
+        // each character covers a 2x2 block of lights
+        assert_eq!(to_ascii(&grid, 4, 2), "@@  \n  - \n");
+        // a preview larger than the grid shows every light
+        assert_eq!(to_ascii(&grid, 100, 100).lines().count(), 4);
+        assert_eq!(to_ascii(&StaticGrid::new(0, 0), 10, 10), "");
+    }
+}
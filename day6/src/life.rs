@@ -0,0 +1,224 @@
+use crate::{GridIndex, Light, StaticGrid};
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Edges {
+    // lights past the edge are always off
+    Bounded,
+    // the grid wraps around, so the top row neighbors the bottom row
+    Toroidal,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Automaton {
+    // indexed by the number of lit neighbors
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+    pub edges: Edges,
+    pub stuck_corners: bool,
+}
+
+impl Default for Automaton {
+    fn default() -> Self {
+        // conway's game of life, B3/S23
+        "B3/S23".parse().unwrap()
+    }
+}
+
+impl FromStr for Automaton {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // birth and survival counts in the usual rulestring notation, e.g. B36/S23
+        let (birth, survival) = s
+            .split_once('/')
+            .ok_or_else(|| format!("expected a rule like B3/S23, got \"{}\"", s))?;
+        let counts = |part: &str, prefix: char| -> Result<[bool; 9], String> {
+            let digits = part
+                .strip_prefix(prefix)
+                .ok_or_else(|| format!("expected \"{}\" to start with {}", part, prefix))?;
+            let mut counts = [false; 9];
+            for digit in digits.chars() {
+                match digit.to_digit(10) {
+                    Some(x) if x < 9 => counts[x as usize] = true,
+                    _ => return Err(format!("bad neighbor count {} in \"{}\"", digit, part)),
+                }
+            }
+            Ok(counts)
+        };
+        Ok(Self {
+            birth: counts(birth, 'B')?,
+            survival: counts(survival, 'S')?,
+            edges: Edges::Bounded,
+            stuck_corners: false,
+        })
+    }
+}
+
+pub fn parse_lights(input: &str) -> Result<StaticGrid, String> {
+    // one row per line, # for on and . for off
+    StaticGrid::parse_chars(input, |c| match c {
+        '#' => Some(Light::On),
+        '.' => Some(Light::Off),
+        _ => None,
+    })
+}
+
+impl Automaton {
+    fn lit_neighbors(&self, grid: &StaticGrid, (row, col): GridIndex) -> usize {
+        let (rows, columns) = (grid.rows(), grid.columns());
+        match self.edges {
+            Edges::Bounded => grid
+                .neighbors8((row, col))
+                .filter(|&x| grid[x] == Light::On)
+                .count(),
+            Edges::Toroidal => {
+                // on a grid less than 3 wide the ring wraps onto the same lights more than once,
+                // so each neighbor is only counted once and the light itself never is
+                let mut neighbors = [(row, col); 8];
+                let mut found = 0;
+                for dr in [rows - 1, 0, 1] {
+                    for dc in [columns - 1, 0, 1] {
+                        let at = ((row + dr) % rows, (col + dc) % columns);
+                        if at != (row, col) && !neighbors[..found].contains(&at) {
+                            neighbors[found] = at;
+                            found += 1;
+                        }
+                    }
+                }
+                neighbors[..found]
+                    .iter()
+                    .filter(|&&x| grid[x] == Light::On)
+                    .count()
+            }
+        }
+    }
+
+    fn stick_corners(&self, grid: &mut StaticGrid) {
+        if !self.stuck_corners || grid.rows() == 0 || grid.columns() == 0 {
+            return;
+        }
+        let (last_row, last_column) = (grid.rows() - 1, grid.columns() - 1);
+        for corner in [
+            (0, 0),
+            (0, last_column),
+            (last_row, 0),
+            (last_row, last_column),
+        ] {
+            grid[corner] = Light::On;
+        }
+    }
+
+    pub fn step(&self, grid: &StaticGrid) -> StaticGrid {
+        // every light changes at once, based on the grid before the step
+        let mut next = StaticGrid::new(grid.rows(), grid.columns());
+        for row in 0..grid.rows() {
+            for col in 0..grid.columns() {
+                let lit = self.lit_neighbors(grid, (row, col));
+                let on = match grid[(row, col)] {
+                    Light::On => self.survival[lit],
+                    Light::Off => self.birth[lit],
+                };
+                if on {
+                    next[(row, col)] = Light::On;
+                }
+            }
+        }
+        self.stick_corners(&mut next);
+        next
+    }
+
+    pub fn run(&self, grid: &StaticGrid, steps: usize) -> StaticGrid {
+        let mut grid = grid.clone();
+        // stuck corners are on from the start, not just after the first step
+        self.stick_corners(&mut grid);
+        for _ in 0..steps {
+            grid = self.step(&grid);
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LightGrid;
+
+    const EXAMPLE: &str = ".#.#.#
+...##.
+#....#
+..#...
+#.#..#
+####..";
+
+    #[test]
+    fn test_parse_lights() {
+        let grid = parse_lights(EXAMPLE).unwrap();
+        assert_eq!((grid.rows(), grid.columns()), (6, 6));
+        assert_eq!(grid.count(), 15);
+        assert_eq!(grid[(0, 1)], Light::On);
+        assert_eq!(grid.to_string(), EXAMPLE);
+        assert!(parse_lights("##\n#").is_err());
+        assert!(parse_lights("#x").is_err());
+        assert_eq!(parse_lights("").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_rules() {
+        let highlife: Automaton = "B36/S23".parse().unwrap();
+        assert!(highlife.birth[3] && highlife.birth[6] && !highlife.birth[2]);
+        assert!("B3S23".parse::<Automaton>().is_err());
+        assert!("B9/S23".parse::<Automaton>().is_err());
+        assert!("S3/B23".parse::<Automaton>().is_err());
+        assert_eq!(Automaton::default().edges, Edges::Bounded);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let grid = parse_lights(EXAMPLE).unwrap();
+        let automaton = Automaton::default();
+        let counts: Vec<u64> = (1..=4).map(|x| automaton.run(&grid, x).count()).collect();
+        assert_eq!(counts, vec![11, 8, 4, 4]);
+    }
+
+    #[test]
+    fn test_stuck_corners() {
+        let grid = parse_lights(EXAMPLE).unwrap();
+        let automaton = Automaton {
+            stuck_corners: true,
+            ..Default::default()
+        };
+        assert_eq!(automaton.run(&grid, 0).count(), 17);
+        assert_eq!(automaton.run(&grid, 5).count(), 17);
+    }
+
+    #[test]
+    fn test_toroidal() {
+        // a glider moves one cell diagonally every four steps, so it wraps back to the start
+        let glider = parse_lights(".#....\n..#...\n###...\n......\n......\n......").unwrap();
+        let automaton = Automaton {
+            edges: Edges::Toroidal,
+            ..Default::default()
+        };
+        assert_eq!(automaton.run(&glider, 24), glider);
+        // on a bounded grid it hits the corner and turns into a block
+        assert_eq!(Automaton::default().run(&glider, 24).count(), 4);
+    }
+
+    #[test]
+    fn test_small_torus() {
+        let automaton = Automaton {
+            edges: Edges::Toroidal,
+            ..Default::default()
+        };
+        // every light on a 2x2 torus has the other three as neighbors, so the block survives
+        let block = parse_lights("##\n##").unwrap();
+        assert_eq!(automaton.run(&block, 3), block);
+        // a light on its own never neighbors itself
+        let single = parse_lights("#").unwrap();
+        assert_eq!(automaton.lit_neighbors(&single, (0, 0)), 0);
+        let row = parse_lights("#.#").unwrap();
+        assert_eq!(automaton.lit_neighbors(&row, (0, 1)), 2);
+        assert_eq!(automaton.lit_neighbors(&row, (0, 0)), 1);
+    }
+}
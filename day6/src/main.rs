@@ -1,41 +1,86 @@
+mod bits;
+mod compressed;
+mod image;
+mod life;
+mod optimize;
+mod query;
+mod replay;
+mod semantics;
+
+use bits::BitGrid;
+use compressed::CompressedGrid;
+use grid::{Grid, GridIndex};
 use lazy_static::lazy_static;
+use life::{Automaton, Edges};
 use regex::Regex;
-use std::{
-    fs,
-    ops::{Index, IndexMut},
-    process,
-};
+use semantics::{SemanticGrid, Semantics};
+use std::{env, fmt, fs, path::Path, process, str::FromStr};
 
-type GridIndex = (usize, usize);
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+// the puzzle's lights form a square this many lights on each side
+const GRID_SIZE: usize = 1000;
+
+// size of the text previews printed by --ascii
+const ASCII_WIDTH: usize = 100;
+const ASCII_HEIGHT: usize = 50;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum Light {
     On,
+    #[default]
     Off,
 }
 
-impl Default for Light {
-    fn default() -> Self {
-        Self::Off
+// how a single light responds to a command, shared by every grid backend
+pub trait Lamp: Default + Clone {
+    fn apply(&mut self, command: GridCommand);
+    fn brightness(&self) -> u32;
+}
+
+impl fmt::Display for Light {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the same characters the automaton reads
+        match self {
+            Light::On => write!(f, "#"),
+            Light::Off => write!(f, "."),
+        }
     }
 }
 
-struct Grid<T> {
-    lights: Vec<T>,
-    rows: usize,
-    columns: usize,
+impl Lamp for Light {
+    fn apply(&mut self, command: GridCommand) {
+        *self = match command {
+            GridCommand::Off => Light::Off,
+            GridCommand::On => Light::On,
+            GridCommand::Toggle => {
+                if *self == Light::On {
+                    Light::Off
+                } else {
+                    Light::On
+                }
+            }
+        }
+    }
+
+    fn brightness(&self) -> u32 {
+        match self {
+            Light::On => 1,
+            Light::Off => 0,
+        }
+    }
 }
 
-impl<T> Grid<T>
-where
-    T: Default + Clone,
-{
-    fn new(rows: usize, columns: usize) -> Self {
-        Self {
-            lights: vec![T::default(); rows * columns],
-            rows,
-            columns,
+impl Lamp for u32 {
+    fn apply(&mut self, command: GridCommand) {
+        match command {
+            GridCommand::Off => *self = self.saturating_sub(1),
+            GridCommand::On => *self += 1,
+            GridCommand::Toggle => *self += 2,
         }
     }
+
+    fn brightness(&self) -> u32 {
+        *self
+    }
 }
 
 type StaticGrid = Grid<Light>;
@@ -47,6 +92,8 @@ pub enum GridCommand {
     Off,
     Toggle,
 }
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct GridInstruction {
     command: GridCommand,
     start_pos: GridIndex,
@@ -63,14 +110,28 @@ impl GridInstruction {
     }
 }
 
+impl fmt::Display for GridInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the same wording as the puzzle input
+        let command = match self.command {
+            GridCommand::On => "turn on",
+            GridCommand::Off => "turn off",
+            GridCommand::Toggle => "toggle",
+        };
+        let ((r0, c0), (r1, c1)) = (self.start_pos, self.end_pos);
+        write!(f, "{} {},{} through {},{}", command, r0, c0, r1, c1)
+    }
+}
+
 pub trait LightGrid {
-    fn count(&self) -> u32;
+    fn count(&self) -> u64;
     fn execute(&mut self, instruction: &GridInstruction);
 }
 
-impl LightGrid for StaticGrid {
-    fn count(&self) -> u32 {
-        self.lights.iter().filter(|&x| *x == Light::On).count() as u32
+impl<T: Lamp> LightGrid for Grid<T> {
+    fn count(&self) -> u64 {
+        // every light can reach u32::MAX under custom semantics, so the total needs more room
+        self.iter().map(|x| x.brightness() as u64).sum()
     }
 
     fn execute(&mut self, instruction: &GridInstruction) {
@@ -78,92 +139,124 @@ impl LightGrid for StaticGrid {
         let (r1, c1) = instruction.end_pos;
         for row in r0..r1 + 1 {
             for col in c0..c1 + 1 {
-                self[(row, col)] = match instruction.command {
-                    GridCommand::Off => Light::Off,
-                    GridCommand::On => Light::On,
-                    GridCommand::Toggle => {
-                        if self[(row, col)] == Light::On {
-                            Light::Off
-                        } else {
-                            Light::On
-                        }
-                    }
-                }
+                self[(row, col)].apply(instruction.command);
             }
         }
     }
 }
 
-impl LightGrid for DynamicGrid {
-    fn count(&self) -> u32 {
-        self.lights.iter().sum()
-    }
+impl FromStr for GridInstruction {
+    type Err = String;
 
-    fn execute(&mut self, instruction: &GridInstruction) {
-        let (r0, c0) = instruction.start_pos;
-        let (r1, c1) = instruction.end_pos;
-        for row in r0..r1 + 1 {
-            for col in c0..c1 + 1 {
-                match instruction.command {
-                    GridCommand::Off => self[(row, col)] = self[(row, col)].saturating_sub(1),
-                    GridCommand::On => self[(row, col)] += 1,
-                    GridCommand::Toggle => self[(row, col)] += 2,
-                }
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref COMMAND: Regex = Regex::new(
+                r"^(toggle|turn off|turn on) ([0-9]+),([0-9]+) through ([0-9]+),([0-9]+)$"
+            )
+            .unwrap();
         }
-    }
-}
 
-impl<T> IndexMut<GridIndex> for Grid<T> {
-    fn index_mut(&mut self, (r, c): GridIndex) -> &mut Self::Output {
-        &mut self.lights[r * self.columns + c]
+        let captures = COMMAND
+            .captures(s.trim())
+            .ok_or_else(|| format!("expected \"<command> r,c through r,c\", got \"{}\"", s))?;
+        let command = match &captures[1] {
+            "toggle" => GridCommand::Toggle,
+            "turn on" => GridCommand::On,
+            _ => GridCommand::Off,
+        };
+        let coordinate = |n: usize| {
+            captures[n]
+                .parse::<usize>()
+                .map_err(|err| format!("bad coordinate {}: {}", &captures[n], err))
+        };
+        let (r0, c0, r1, c1) = (
+            coordinate(2)?,
+            coordinate(3)?,
+            coordinate(4)?,
+            coordinate(5)?,
+        );
+        // the text names two opposite corners, so either order describes the same rectangle
+        Ok(GridInstruction {
+            command,
+            start_pos: (r0.min(r1), c0.min(c1)),
+            end_pos: (r0.max(r1), c0.max(c1)),
+        })
     }
 }
 
-impl<T> Index<GridIndex> for Grid<T> {
-    fn index(&self, (r, c): GridIndex) -> &Self::Output {
-        if r < self.rows && c < self.columns {
-            &self.lights[r * self.columns + c]
-        } else {
-            panic!("Index Out of Bounds Error");
+fn parse(input: &str) -> Result<Vec<GridInstruction>, Vec<String>> {
+    // reports every line that fails rather than stopping at the first, blank lines are skipped
+    let mut instructions = Vec::new();
+    let mut errors = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.parse() {
+            Ok(instruction) => instructions.push(instruction),
+            Err(err) => errors.push(format!("line {}: {}", n + 1, err)),
         }
     }
-
-    type Output = T;
-}
-
-fn parse(input: &str) -> Vec<GridInstruction> {
-    lazy_static! {
-        static ref COMMAND: Regex =
-            Regex::new(r"(toggle|turn off|turn on) ([0-9]+),([0-9]+) through ([0-9]+),([0-9]+)")
-                .unwrap();
+    match errors.is_empty() {
+        true => Ok(instructions),
+        false => Err(errors),
     }
+}
 
-    //iterate over the lines and collect grid instructions
-    input
-        .lines()
-        .map(|x| {
-            if let Some(captures) = COMMAND.captures(x) {
-                let command = match &captures[1] {
-                    "toggle" => GridCommand::Toggle,
-                    "turn on" => GridCommand::On,
-                    "turn off" => GridCommand::Off,
-                    &_ => panic!("Could not Parse Input"),
-                };
-                let start_pos: GridIndex =
-                    (captures[2].parse().unwrap(), captures[3].parse().unwrap());
-                let end_pos: GridIndex =
-                    (captures[4].parse().unwrap(), captures[5].parse().unwrap());
-                GridInstruction {
-                    command,
-                    start_pos,
-                    end_pos,
-                }
+fn validate(
+    instructions: &[GridInstruction],
+    rows: usize,
+    columns: usize,
+) -> Result<(), Vec<String>> {
+    // catches instructions built by hand that parse would never produce or that leave the grid
+    let errors: Vec<String> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(n, instruction)| {
+            let ((r0, c0), (r1, c1)) = (instruction.start_pos, instruction.end_pos);
+            if r0 > r1 || c0 > c1 {
+                Some(format!(
+                    "instruction {}: {} has its corners reversed",
+                    n + 1,
+                    instruction
+                ))
+            } else if r1 >= rows || c1 >= columns {
+                Some(format!(
+                    "instruction {}: {} is outside the {}x{} grid",
+                    n + 1,
+                    instruction,
+                    rows,
+                    columns
+                ))
             } else {
-                panic!("Could not Parse Input")
+                None
             }
         })
-        .collect()
+        .collect();
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
+
+fn rectangle_arg(args: &[String], n: usize) -> (GridIndex, GridIndex) {
+    // the argument after a flag at n written as r0,c0,r1,c1
+    let corners: Vec<usize> = args
+        .get(n + 1)
+        .map(|x| x.split(',').filter_map(|x| x.parse().ok()).collect())
+        .unwrap_or_default();
+    match corners[..] {
+        [r0, c0, r1, c1] if r0 <= r1 && c0 <= c1 && r1 < GRID_SIZE && c1 < GRID_SIZE => {
+            ((r0, c0), (r1, c1))
+        }
+        _ => {
+            eprintln!(
+                "{} requires a rectangle inside the grid, e.g. 0,0,9,9",
+                args[n]
+            );
+            process::exit(1);
+        }
+    }
 }
 
 fn main() {
@@ -172,24 +265,257 @@ fn main() {
         process::exit(1);
     });
 
-    let commands = parse(&input);
-    let mut static_grid = StaticGrid::new(1000, 1000);
-    let mut dynamic_grid = DynamicGrid::new(1000, 1000);
-    for command in commands {
-        static_grid.execute(&command);
-        dynamic_grid.execute(&command);
+    let commands = parse(&input)
+        .and_then(|commands| validate(&commands, GRID_SIZE, GRID_SIZE).map(|_| commands))
+        .unwrap_or_else(|errors| {
+            for err in errors {
+                eprintln!("Instruction Error {}", err);
+            }
+            process::exit(1);
+        });
+    let args: Vec<String> = env::args().skip(1).collect();
+    // the compressed grids cost the same however large the instruction rectangles are, the bit
+    // grid packs part one's lights into words
+    let compressed = args.iter().any(|x| x == "--compressed");
+    let mut static_grid: Box<dyn LightGrid> = if args.iter().any(|x| x == "--bits") {
+        Box::new(BitGrid::new(GRID_SIZE, GRID_SIZE))
+    } else if compressed {
+        Box::new(CompressedGrid::<Light>::new(GRID_SIZE, GRID_SIZE))
+    } else {
+        Box::new(StaticGrid::new(GRID_SIZE, GRID_SIZE))
+    };
+    // custom brightness rules for part two, e.g. --semantics toggle=multiply:2,clamp=0:10
+    let semantics = match args.iter().position(|x| x == "--semantics") {
+        Some(n) => args
+            .get(n + 1)
+            .ok_or_else(|| String::from("--semantics requires a rule list"))
+            .and_then(|x| x.parse::<Semantics>())
+            .unwrap_or_else(|err| {
+                eprintln!("Argument Error: {}", err);
+                process::exit(1);
+            }),
+        None => Semantics::default(),
+    };
+    let mut dynamic_grid: Box<dyn LightGrid> = if compressed {
+        Box::new(SemanticGrid::<CompressedGrid<u32>>::new(
+            GRID_SIZE, GRID_SIZE, semantics,
+        ))
+    } else {
+        Box::new(SemanticGrid::<DynamicGrid>::new(
+            GRID_SIZE, GRID_SIZE, semantics,
+        ))
+    };
+    // part one only cares about the final on/off state, so its instructions can be trimmed first
+    let static_commands = if args.iter().any(|x| x == "--optimize") {
+        let optimized = optimize::optimize(&commands);
+        println!(
+            "Optimizer eliminated {} of {} instructions ({} overwritten, {} cancelled, {} merged)",
+            optimized.eliminated(),
+            commands.len(),
+            optimized.overwritten,
+            optimized.cancelled,
+            optimized.merged
+        );
+        optimized.instructions
+    } else {
+        commands.clone()
+    };
+    for command in static_commands.iter() {
+        static_grid.execute(command);
+    }
+    for command in commands.iter() {
+        dynamic_grid.execute(command);
     }
     println!("Part One Solution: {}", static_grid.count());
     println!("Part Two Solution: {}", dynamic_grid.count());
+
+    // step by step history of part one's display
+    if let Some(n) = args.iter().position(|x| x == "--region") {
+        let (top_left, bottom_right) = rectangle_arg(&args, n);
+        let mut grid = StaticGrid::new(GRID_SIZE, GRID_SIZE);
+        let steps = replay::changes_in(&mut grid, &commands, top_left, bottom_right);
+        for step in steps.iter() {
+            println!("{}", replay::log_line(step, &commands[step.instruction]));
+        }
+    }
+    if let Some(n) = args.iter().position(|x| x == "--diff") {
+        let path = args.get(n + 1).unwrap_or_else(|| {
+            eprintln!("--diff requires an output path");
+            process::exit(1);
+        });
+        let mut log = String::new();
+        replay::replay(
+            &mut StaticGrid::new(GRID_SIZE, GRID_SIZE),
+            &commands,
+            |step, _| {
+                log.push_str(&replay::log_line(step, &commands[step.instruction]));
+                log.push('\n');
+            },
+        );
+        fs::write(path, log).unwrap_or_else(|err| {
+            eprintln!("File Write Error {}", err);
+            process::exit(1);
+        });
+    }
+    // writes a numbered pbm after every nth instruction, e.g. --frames frames --every 10
+    if let Some(n) = args.iter().position(|x| x == "--frames") {
+        let directory = args.get(n + 1).unwrap_or_else(|| {
+            eprintln!("--frames requires an output directory");
+            process::exit(1);
+        });
+        let every = match args.iter().position(|x| x == "--every") {
+            Some(n) => args
+                .get(n + 1)
+                .and_then(|x| x.parse().ok())
+                .filter(|&x: &usize| x > 0)
+                .unwrap_or_else(|| {
+                    eprintln!("--every requires a positive number");
+                    process::exit(1);
+                }),
+            None => 1,
+        };
+        let written = fs::create_dir_all(directory).and_then(|_| {
+            let mut result = Ok(());
+            replay::replay(
+                &mut StaticGrid::new(GRID_SIZE, GRID_SIZE),
+                &commands,
+                |step, grid| {
+                    let last = step.instruction + 1 == commands.len();
+                    if result.is_ok() && ((step.instruction + 1) % every == 0 || last) {
+                        let path = Path::new(directory)
+                            .join(format!("frame_{:04}.pbm", step.instruction + 1));
+                        result = fs::write(path, image::to_pbm(grid));
+                    }
+                },
+            );
+            result
+        });
+        if let Err(err) = written {
+            eprintln!("File Write Error {}", err);
+            process::exit(1);
+        }
+    }
+
+    // runs a cellular automaton on a # and . map instead of the instructions,
+    // e.g. --life day18.txt 100 --rule B3/S23 --toroidal --stuck-corners
+    if let Some(n) = args.iter().position(|x| x == "--life") {
+        let grid = args
+            .get(n + 1)
+            .ok_or_else(|| String::from("--life requires a path"))
+            .and_then(|path| fs::read_to_string(path).map_err(|err| err.to_string()))
+            .and_then(|text| life::parse_lights(&text));
+        let steps = args.get(n + 2).and_then(|x| x.parse().ok());
+        let automaton = match args.iter().position(|x| x == "--rule") {
+            Some(n) => args
+                .get(n + 1)
+                .ok_or_else(|| String::from("--rule requires a rule like B3/S23"))
+                .and_then(|x| x.parse()),
+            None => Ok(Automaton::default()),
+        };
+        match (grid, steps, automaton) {
+            (Ok(grid), Some(steps), Ok(mut automaton)) => {
+                if args.iter().any(|x| x == "--toroidal") {
+                    automaton.edges = Edges::Toroidal;
+                }
+                automaton.stuck_corners = args.iter().any(|x| x == "--stuck-corners");
+                let grid = automaton.run(&grid, steps);
+                println!("{} lights on after {} steps", grid.count(), steps);
+            }
+            (Err(err), _, _) | (_, _, Err(err)) => {
+                eprintln!("Life Error: {}", err);
+                process::exit(1);
+            }
+            (_, None, _) => {
+                eprintln!("--life requires a number of steps after the path");
+                process::exit(1);
+            }
+        }
+    }
+
+    // pictures and queries of the final displays, drawn from dense grids whichever backend
+    // found the counts
+    let dense_flags = ["--pbm", "--pgm", "--ascii", "--query", "--regions"];
+    if !args.iter().any(|x| dense_flags.contains(&x.as_str())) {
+        return;
+    }
+    let mut static_grid = StaticGrid::new(GRID_SIZE, GRID_SIZE);
+    let mut semantic_grid = SemanticGrid::<DynamicGrid>::new(GRID_SIZE, GRID_SIZE, semantics);
+    for command in commands.iter() {
+        static_grid.execute(command);
+        semantic_grid.execute(command);
+    }
+    let dynamic_grid = semantic_grid.grid();
+    for (n, arg) in args.iter().enumerate() {
+        let image = match arg.as_str() {
+            "--pbm" => image::to_pbm(&static_grid),
+            "--pgm" => image::to_pgm(dynamic_grid),
+            "--ascii" => {
+                print!(
+                    "{}",
+                    image::to_ascii(&static_grid, ASCII_WIDTH, ASCII_HEIGHT)
+                );
+                print!(
+                    "{}",
+                    image::to_ascii(dynamic_grid, ASCII_WIDTH, ASCII_HEIGHT)
+                );
+                continue;
+            }
+            // lights on and total brightness inside a rectangle, e.g. --query 0,0,499,499
+            "--query" => {
+                let (top_left, bottom_right) = rectangle_arg(&args, n);
+                println!(
+                    "{} lights on, {} total brightness",
+                    query::PrefixSums::lit(&static_grid).sum(top_left, bottom_right),
+                    query::PrefixSums::new(dynamic_grid).sum(top_left, bottom_right)
+                );
+                continue;
+            }
+            "--regions" => {
+                if let Some((top_left, bottom_right)) = query::lit_bounds(&static_grid) {
+                    println!("Lit lights span {:?} to {:?}", top_left, bottom_right);
+                }
+                println!("{} separate lit regions", query::lit_regions(&static_grid));
+                if let Some((position, brightness)) = query::brightest(dynamic_grid) {
+                    println!("Brightest light is {:?} at {}", position, brightness);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let path = args.get(n + 1).unwrap_or_else(|| {
+            eprintln!("{} requires an output path", arg);
+            process::exit(1);
+        });
+        fs::write(path, image).unwrap_or_else(|err| {
+            eprintln!("File Write Error {}", err);
+            process::exit(1);
+        });
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use grid::lcg::Lcg;
+
+    // shared by the backend tests, every rectangle fits inside a size by size grid
+    pub fn random_instructions(count: usize, size: usize, seed: u64) -> Vec<GridInstruction> {
+        let mut lcg = Lcg::new(seed);
+        (0..count)
+            .map(|_| {
+                let command =
+                    *lcg.choose(&[GridCommand::On, GridCommand::Off, GridCommand::Toggle]);
+                let (r0, c0) = (lcg.below(size), lcg.below(size));
+                let (r1, c1) = (r0 + lcg.below(size - r0), c0 + lcg.below(size - c0));
+                GridInstruction::new(command, (r0, c0), (r1, c1))
+            })
+            .collect()
+    }
+
     #[test]
     fn test_grid_creation() {
         let grid = StaticGrid::new(3, 3);
-        assert_eq!(grid.lights.len(), 9);
+        assert_eq!(grid.as_slice().len(), 9);
         assert_eq!(grid[(0, 0)], Light::Off);
     }
     #[test]
@@ -207,10 +533,10 @@ mod test {
         let command = GridInstruction::new(GridCommand::Toggle, (0, 0), (1, 1));
         grid.execute(&command);
 
-        let mut expected_on = vec![Light::On; 4];
+        let mut expected_on = [Light::On; 4];
         expected_on[0] = Light::Off;
 
-        for (actual, expected) in grid.lights.iter().zip(expected_on.iter()) {
+        for (actual, expected) in grid.iter().zip(expected_on.iter()) {
             assert_eq!(expected, actual);
         }
     }
@@ -222,7 +548,7 @@ mod test {
         assert_eq!(grid[(0, 0)], Light::On);
 
         grid[(1, 0)] = Light::On;
-        assert_eq!(grid.lights[3], Light::On);
+        assert_eq!(grid.as_slice()[3], Light::On);
     }
     #[test]
     fn test_switch() {
@@ -230,13 +556,13 @@ mod test {
         let command = GridInstruction::new(GridCommand::On, (0, 0), (1, 1));
 
         grid.execute(&command);
-        let mut expected_on = vec![Light::Off; 9];
+        let mut expected_on = [Light::Off; 9];
         expected_on[0] = Light::On;
         expected_on[1] = Light::On;
         expected_on[3] = Light::On;
         expected_on[4] = Light::On;
 
-        for (actual, expected) in grid.lights.iter().zip(expected_on.iter()) {
+        for (actual, expected) in grid.iter().zip(expected_on.iter()) {
             assert_eq!(expected, actual);
         }
     }
@@ -244,6 +570,50 @@ mod test {
     #[test]
     fn test_parsing() {
         let results = parse("toggle 461,550 through 564,900\nturn off 370,39 through 425,839");
-        assert_eq!(results.len(), 2)
+        assert_eq!(results.unwrap().len(), 2)
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let errors =
+            parse("turn on 0,0 through 9,9\nflip 0,0 through 1,1\n\ntoggle 1,x through 2,2")
+                .err()
+                .unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("line 2: "));
+        assert!(errors[1].starts_with("line 4: "));
+        let err = parse("turn on 0,0 through 99999999999999999999999,1")
+            .err()
+            .unwrap();
+        assert!(err[0].contains("bad coordinate"));
+        // reversed corners describe the same rectangle
+        let instruction: GridInstruction = "turn off 5,1 through 2,3".parse().unwrap();
+        assert_eq!(instruction.to_string(), "turn off 2,1 through 5,3");
+    }
+
+    #[test]
+    fn test_validate() {
+        let instructions = vec![
+            GridInstruction::new(GridCommand::On, (0, 0), (2, 2)),
+            GridInstruction::new(GridCommand::On, (2, 2), (0, 0)),
+            GridInstruction::new(GridCommand::Toggle, (0, 0), (2, 3)),
+        ];
+        assert!(validate(&instructions[..1], 3, 3).is_ok());
+        let errors = validate(&instructions, 3, 3).err().unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                "instruction 2: turn on 2,2 through 0,0 has its corners reversed",
+                "instruction 3: toggle 0,0 through 2,3 is outside the 3x3 grid"
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Index Out of Bounds Error")]
+    fn test_index_mut_bounds() {
+        let mut grid = StaticGrid::new(2, 2);
+        // one past the end of a row would land in the next row without the check
+        grid[(0, 2)] = Light::On;
     }
 }
@@ -0,0 +1,203 @@
+use crate::{GridCommand, GridIndex, GridInstruction};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Optimized {
+    pub instructions: Vec<GridInstruction>,
+    pub overwritten: usize,
+    pub cancelled: usize,
+    pub merged: usize,
+}
+
+impl Optimized {
+    pub fn eliminated(&self) -> usize {
+        self.overwritten + self.cancelled + self.merged
+    }
+}
+
+fn band(cuts: &[usize], at: usize) -> usize {
+    // every coordinate passed in is one of the cuts
+    cuts.binary_search(&at).unwrap()
+}
+
+fn drop_overwritten(instructions: &[GridInstruction]) -> (Vec<GridInstruction>, usize) {
+    // walks backwards keeping track of which lights a later on or off will set no matter what,
+    // anything landing entirely inside that area can't affect the final grid. the area is kept
+    // on a grid compressed to the rectangle edges so its size doesn't matter
+    let mut row_cuts: Vec<usize> = Vec::new();
+    let mut column_cuts: Vec<usize> = Vec::new();
+    for instruction in instructions.iter() {
+        let ((r0, c0), (r1, c1)) = (instruction.start_pos, instruction.end_pos);
+        row_cuts.extend([r0, r1 + 1]);
+        column_cuts.extend([c0, c1 + 1]);
+    }
+    row_cuts.sort_unstable();
+    row_cuts.dedup();
+    column_cuts.sort_unstable();
+    column_cuts.dedup();
+    let mut covered = vec![vec![false; column_cuts.len()]; row_cuts.len()];
+
+    let mut kept = Vec::new();
+    let mut overwritten = 0;
+    for instruction in instructions.iter().rev() {
+        let ((r0, c0), (r1, c1)) = (instruction.start_pos, instruction.end_pos);
+        // reversed rectangles cover nothing, so they're as good as overwritten
+        if r0 > r1 || c0 > c1 {
+            overwritten += 1;
+            continue;
+        }
+        let rows = band(&row_cuts, r0)..band(&row_cuts, r1 + 1);
+        let columns = band(&column_cuts, c0)..band(&column_cuts, c1 + 1);
+        if covered[rows.clone()]
+            .iter()
+            .all(|row| row[columns.clone()].iter().all(|&x| x))
+        {
+            overwritten += 1;
+            continue;
+        }
+        if instruction.command != GridCommand::Toggle {
+            for row in covered[rows].iter_mut() {
+                row[columns.clone()].iter_mut().for_each(|x| *x = true);
+            }
+        }
+        kept.push(*instruction);
+    }
+    kept.reverse();
+    (kept, overwritten)
+}
+
+fn merge_rectangles(a: &GridInstruction, b: &GridInstruction) -> Option<(GridIndex, GridIndex)> {
+    // two rectangles sharing a whole edge make one bigger rectangle
+    let ((ar0, ac0), (ar1, ac1)) = (a.start_pos, a.end_pos);
+    let ((br0, bc0), (br1, bc1)) = (b.start_pos, b.end_pos);
+    if (ar0, ar1) == (br0, br1) && (ac1 + 1 == bc0 || bc1 + 1 == ac0) {
+        Some(((ar0, ac0.min(bc0)), (ar1, ac1.max(bc1))))
+    } else if (ac0, ac1) == (bc0, bc1) && (ar1 + 1 == br0 || br1 + 1 == ar0) {
+        Some(((ar0.min(br0), ac0), (ar1.max(br1), ac1)))
+    } else {
+        None
+    }
+}
+
+pub fn optimize(instructions: &[GridInstruction]) -> Optimized {
+    // only keeps the final on/off state, so part one's count is preserved but part two's
+    // brightness isn't, toggling twice adds 2 there instead of cancelling
+    let (kept, overwritten) = drop_overwritten(instructions);
+    let mut optimized = Optimized {
+        overwritten,
+        ..Default::default()
+    };
+
+    // back to back toggles of the same rectangle cancel, ones side by side become one toggle
+    for instruction in kept {
+        let last = optimized.instructions.last().copied();
+        match last {
+            Some(last)
+                if last.command == GridCommand::Toggle
+                    && instruction.command == GridCommand::Toggle =>
+            {
+                if (last.start_pos, last.end_pos) == (instruction.start_pos, instruction.end_pos) {
+                    optimized.instructions.pop();
+                    optimized.cancelled += 2;
+                } else if let Some((start_pos, end_pos)) = merge_rectangles(&last, &instruction) {
+                    *optimized.instructions.last_mut().unwrap() =
+                        GridInstruction::new(GridCommand::Toggle, start_pos, end_pos);
+                    optimized.merged += 1;
+                } else {
+                    optimized.instructions.push(instruction);
+                }
+            }
+            _ => optimized.instructions.push(instruction),
+        }
+    }
+    optimized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bits::BitGrid;
+    use crate::test::random_instructions;
+    use crate::{parse, LightGrid, StaticGrid};
+
+    fn lines(optimized: &Optimized) -> Vec<String> {
+        optimized
+            .instructions
+            .iter()
+            .map(|x| x.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_overwritten() {
+        let instructions = parse(
+            "turn on 0,0 through 9,9
+toggle 2,2 through 3,3
+turn off 0,0 through 4,9
+turn on 5,0 through 9,9
+toggle 5,5 through 6,6",
+        )
+        .unwrap();
+        let optimized = optimize(&instructions);
+        // the first two are covered by the halves below them, a toggle never covers anything
+        assert_eq!(
+            lines(&optimized),
+            vec![
+                "turn off 0,0 through 4,9",
+                "turn on 5,0 through 9,9",
+                "toggle 5,5 through 6,6"
+            ]
+        );
+        assert_eq!(optimized.overwritten, 2);
+        assert_eq!(optimized.eliminated(), 2);
+    }
+
+    #[test]
+    fn test_toggles() {
+        let instructions = parse(
+            "turn on 4,4 through 5,5
+toggle 0,0 through 1,1
+toggle 0,2 through 1,5
+toggle 2,0 through 3,5
+toggle 0,0 through 3,5
+toggle 9,9 through 9,9",
+        )
+        .unwrap();
+        let optimized = optimize(&instructions);
+        // the first three toggles merge into the fourth's rectangle and then cancel with it
+        assert_eq!(
+            lines(&optimized),
+            vec!["turn on 4,4 through 5,5", "toggle 9,9 through 9,9"]
+        );
+        assert_eq!((optimized.merged, optimized.cancelled), (2, 2));
+        assert_eq!(optimized.eliminated(), 4);
+    }
+
+    #[test]
+    fn test_preserves_count() {
+        for seed in 0..30 {
+            let instructions = random_instructions(60, 25, seed);
+            let optimized = optimize(&instructions);
+            assert_eq!(
+                optimized.instructions.len() + optimized.eliminated(),
+                instructions.len()
+            );
+            let (mut original, mut reduced) = (StaticGrid::new(25, 25), StaticGrid::new(25, 25));
+            instructions.iter().for_each(|x| original.execute(x));
+            optimized
+                .instructions
+                .iter()
+                .for_each(|x| reduced.execute(x));
+            assert_eq!(reduced.as_slice(), original.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_input() {
+        let instructions = parse(include_str!("../input.txt")).unwrap();
+        let optimized = optimize(&instructions);
+        let mut grid = BitGrid::new(1000, 1000);
+        optimized.instructions.iter().for_each(|x| grid.execute(x));
+        assert_eq!(grid.count(), 543903);
+        assert!(optimized.instructions.len() < instructions.len());
+    }
+}
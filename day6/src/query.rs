@@ -0,0 +1,165 @@
+use crate::{Grid, GridIndex, Lamp, Light};
+
+// summed brightness of every rectangle touching the top left corner, with a zero row and column
+// in front so any rectangle's sum is four lookups
+pub struct PrefixSums {
+    sums: Vec<u64>,
+    rows: usize,
+    columns: usize,
+}
+
+impl PrefixSums {
+    pub fn new<T: Lamp>(grid: &Grid<T>) -> Self {
+        let width = grid.columns() + 1;
+        let mut sums = vec![0; (grid.rows() + 1) * width];
+        for row in 0..grid.rows() {
+            let mut row_total = 0;
+            for col in 0..grid.columns() {
+                row_total += grid[(row, col)].brightness() as u64;
+                sums[(row + 1) * width + col + 1] = sums[row * width + col + 1] + row_total;
+            }
+        }
+        Self {
+            sums,
+            rows: grid.rows(),
+            columns: grid.columns(),
+        }
+    }
+
+    pub fn lit<T: Lamp>(grid: &Grid<T>) -> Self {
+        // counts lights with any brightness at all instead of adding it up
+        Self::new(&grid.map(|x| match x.brightness() {
+            0 => Light::Off,
+            _ => Light::On,
+        }))
+    }
+
+    pub fn sum(&self, (r0, c0): GridIndex, (r1, c1): GridIndex) -> u64 {
+        // inclusive corners like an instruction, reversed rectangles are empty
+        if r0 > r1 || c0 > c1 {
+            return 0;
+        }
+        if r1 >= self.rows || c1 >= self.columns {
+            panic!("Index Out of Bounds Error");
+        }
+        let width = self.columns + 1;
+        let at = |row: usize, col: usize| self.sums[row * width + col];
+        at(r1 + 1, c1 + 1) + at(r0, c0) - at(r0, c1 + 1) - at(r1 + 1, c0)
+    }
+}
+
+pub fn brightest<T: Lamp>(grid: &Grid<T>) -> Option<(GridIndex, u32)> {
+    // the first brightest light in reading order
+    let mut best: Option<(GridIndex, u32)> = None;
+    for (position, light) in grid.indexed_iter() {
+        let brightness = light.brightness();
+        if best.is_none_or(|(_, x)| brightness > x) {
+            best = Some((position, brightness));
+        }
+    }
+    best
+}
+
+pub fn lit_bounds<T: Lamp>(grid: &Grid<T>) -> Option<(GridIndex, GridIndex)> {
+    // the smallest rectangle holding every lit light
+    let mut bounds: Option<(GridIndex, GridIndex)> = None;
+    for ((row, col), light) in grid.indexed_iter() {
+        if light.brightness() == 0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            None => ((row, col), (row, col)),
+            Some(((r0, c0), (r1, c1))) => ((r0.min(row), c0.min(col)), (r1.max(row), c1.max(col))),
+        });
+    }
+    bounds
+}
+
+pub fn lit_regions<T: Lamp>(grid: &Grid<T>) -> usize {
+    // groups of lit lights joined up, down, left or right, filled with a stack so large regions
+    // don't overflow, unlit lights start out seen so they never begin a region
+    let mut seen = grid.map(|x| x.brightness() == 0);
+    let mut regions = 0;
+    let mut stack = Vec::new();
+    for start in grid.indexed_iter().map(|(position, _)| position) {
+        if seen[start] {
+            continue;
+        }
+        regions += 1;
+        seen[start] = true;
+        stack.push(start);
+        while let Some(position) = stack.pop() {
+            for next in grid.neighbors4(position) {
+                if !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::random_instructions;
+    use crate::{parse, DynamicGrid, LightGrid, StaticGrid};
+
+    fn grid_from(instructions: &str, rows: usize, columns: usize) -> DynamicGrid {
+        let mut grid = DynamicGrid::new(rows, columns);
+        for instruction in parse(instructions).unwrap().iter() {
+            grid.execute(instruction);
+        }
+        grid
+    }
+
+    #[test]
+    fn test_prefix_sums() {
+        let mut grid = DynamicGrid::new(15, 12);
+        for instruction in random_instructions(30, 12, 8) {
+            grid.execute(&instruction);
+        }
+        let sums = PrefixSums::new(&grid);
+        assert_eq!(sums.sum((0, 0), (14, 11)), grid.count());
+        for (r0, c0, r1, c1) in [(0, 0, 0, 0), (3, 2, 9, 7), (5, 0, 14, 11), (2, 2, 1, 1)] {
+            let mut expected = 0;
+            for row in r0..r1 + 1 {
+                for col in c0..c1 + 1 {
+                    expected += grid[(row, col)] as u64;
+                }
+            }
+            assert_eq!(sums.sum((r0, c0), (r1, c1)), expected);
+        }
+    }
+
+    #[test]
+    fn test_brightest_and_bounds() {
+        let grid = grid_from("turn on 2,3 through 4,6\ntoggle 3,5 through 3,8", 10, 10);
+        assert_eq!(brightest(&grid), Some(((3, 5), 3)));
+        assert_eq!(lit_bounds(&grid), Some(((2, 3), (4, 8))));
+        let lit = PrefixSums::lit(&grid);
+        assert_eq!(lit.sum((0, 0), (3, 4)), 4);
+        assert_eq!(lit.sum((0, 0), (9, 9)), 14);
+        assert_eq!(lit_bounds(&StaticGrid::new(4, 4)), None);
+        assert_eq!(brightest(&StaticGrid::new(0, 0)), None);
+    }
+
+    #[test]
+    fn test_lit_regions() {
+        let grid = grid_from(
+            "turn on 0,0 through 1,1\nturn on 2,2 through 2,2\nturn on 0,4 through 3,4\nturn on 4,0 through 4,4",
+            5,
+            5,
+        );
+        // the diagonal light doesn't join the square, but the column and the bottom row touch
+        assert_eq!(lit_regions(&grid), 3);
+        let grid = grid_from(
+            "turn on 0,0 through 999,999\nturn off 500,0 through 500,999",
+            1000,
+            1000,
+        );
+        assert_eq!(lit_regions(&grid), 2);
+        assert_eq!(lit_regions(&StaticGrid::new(3, 3)), 0);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{Grid, GridIndex, GridInstruction, Lamp};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub instruction: usize,
+    pub changed: usize,
+    pub count: u64,
+}
+
+fn execute_counting<T: Lamp + PartialEq>(
+    grid: &mut Grid<T>,
+    instruction: &GridInstruction,
+    (top, left): GridIndex,
+    (bottom, right): GridIndex,
+) -> (usize, i64) {
+    // runs the instruction light by light, returning how many lights inside the region changed
+    // and how much the brightness of the whole grid moved
+    let (r0, c0) = instruction.start_pos;
+    let (r1, c1) = instruction.end_pos;
+    let (mut changed, mut delta) = (0, 0);
+    for row in r0..r1 + 1 {
+        for col in c0..c1 + 1 {
+            let before = grid[(row, col)].clone();
+            grid[(row, col)].apply(instruction.command);
+            let after = &grid[(row, col)];
+            delta += after.brightness() as i64 - before.brightness() as i64;
+            if *after != before && (top..=bottom).contains(&row) && (left..=right).contains(&col) {
+                changed += 1;
+            }
+        }
+    }
+    (changed, delta)
+}
+
+fn replay_region<T: Lamp + PartialEq>(
+    grid: &mut Grid<T>,
+    instructions: &[GridInstruction],
+    top_left: GridIndex,
+    bottom_right: GridIndex,
+    mut after_step: impl FnMut(&Step, &Grid<T>),
+) {
+    let mut count = grid.iter().map(|x| x.brightness() as i64).sum::<i64>();
+    for (n, instruction) in instructions.iter().enumerate() {
+        let (changed, delta) = execute_counting(grid, instruction, top_left, bottom_right);
+        count += delta;
+        let step = Step {
+            instruction: n,
+            changed,
+            count: count as u64,
+        };
+        after_step(&step, grid);
+    }
+}
+
+pub fn replay<T: Lamp + PartialEq>(
+    grid: &mut Grid<T>,
+    instructions: &[GridInstruction],
+    after_step: impl FnMut(&Step, &Grid<T>),
+) {
+    // executes the instructions in order, handing every intermediate grid to the callback
+    let bottom_right = match (grid.rows().checked_sub(1), grid.columns().checked_sub(1)) {
+        (Some(row), Some(col)) => (row, col),
+        // an empty grid has no corner, and no instruction can land inside it
+        _ if instructions.is_empty() => return,
+        _ => panic!("Index Out of Bounds Error"),
+    };
+    replay_region(grid, instructions, (0, 0), bottom_right, after_step)
+}
+
+pub fn changes_in<T: Lamp + PartialEq>(
+    grid: &mut Grid<T>,
+    instructions: &[GridInstruction],
+    top_left: GridIndex,
+    bottom_right: GridIndex,
+) -> Vec<Step> {
+    // the steps that changed at least one light inside the region, counting only those lights
+    let mut steps = Vec::new();
+    replay_region(grid, instructions, top_left, bottom_right, |step, _| {
+        if step.changed > 0 {
+            steps.push(*step);
+        }
+    });
+    steps
+}
+
+pub fn log_line(step: &Step, instruction: &GridInstruction) -> String {
+    // numbered like validate's errors, parse skips blank lines so this can differ from the line
+    format!(
+        "instruction {}: {} changed {} lights, {} total",
+        step.instruction + 1,
+        instruction,
+        step.changed,
+        step.count
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::random_instructions;
+    use crate::{parse, DynamicGrid, LightGrid, StaticGrid};
+
+    const INSTRUCTIONS: &str = "turn on 0,0 through 3,3
+toggle 0,0 through 0,3
+turn off 2,2 through 5,5
+turn on 0,0 through 0,0";
+
+    #[test]
+    fn test_steps() {
+        let mut grid = StaticGrid::new(6, 6);
+        let mut steps = Vec::new();
+        replay(&mut grid, &parse(INSTRUCTIONS).unwrap(), |step, grid| {
+            assert_eq!(step.count, grid.count());
+            steps.push((step.changed, step.count));
+        });
+        assert_eq!(steps, vec![(16, 16), (4, 12), (4, 8), (1, 9)]);
+
+        // the bottom row and right column count as part of the grid
+        let mut grid = StaticGrid::new(2, 2);
+        let instructions = parse("toggle 1,1 through 1,1").unwrap();
+        replay(&mut grid, &instructions, |step, _| {
+            assert_eq!(step.changed, 1)
+        });
+        replay(&mut StaticGrid::new(0, 0), &[], |_, _| unreachable!());
+    }
+
+    #[test]
+    fn test_counts_match_execute() {
+        let instructions = random_instructions(40, 20, 3);
+        let mut grid = DynamicGrid::new(20, 20);
+        let mut reference = DynamicGrid::new(20, 20);
+        replay(&mut grid, &instructions, |step, grid| {
+            reference.execute(&instructions[step.instruction]);
+            assert_eq!(step.count, reference.count());
+            assert_eq!(grid.as_slice(), reference.as_slice());
+        });
+    }
+
+    #[test]
+    fn test_changes_in_region() {
+        let instructions = parse(INSTRUCTIONS).unwrap();
+        let mut grid = StaticGrid::new(6, 6);
+        let steps = changes_in(&mut grid, &instructions, (4, 4), (5, 5));
+        // the region starts off and turning it off again is not a change
+        assert!(steps.is_empty());
+
+        let mut grid = StaticGrid::new(6, 6);
+        let steps = changes_in(&mut grid, &instructions, (0, 0), (0, 1));
+        let lines: Vec<usize> = steps.iter().map(|x| x.instruction).collect();
+        assert_eq!(lines, vec![0, 1, 3]);
+        assert_eq!(steps[1].changed, 2);
+        assert_eq!(
+            log_line(&steps[2], &instructions[3]),
+            "instruction 4: turn on 0,0 through 0,0 changed 1 lights, 9 total"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use crate::compressed::CompressedGrid;
+use crate::{DynamicGrid, GridCommand, GridInstruction, LightGrid};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operation {
+    Add(u32),
+    Subtract(u32),
+    Set(u32),
+    Multiply(u32),
+}
+
+impl Operation {
+    fn apply(self, value: u32) -> u32 {
+        match self {
+            Operation::Add(x) => value.saturating_add(x),
+            Operation::Subtract(x) => value.saturating_sub(x),
+            Operation::Set(x) => x,
+            Operation::Multiply(x) => value.saturating_mul(x),
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // e.g. add:2, the amount is required for every operation
+        let (name, amount) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected <operation>:<amount>, got \"{}\"", s))?;
+        let amount: u32 = amount
+            .parse()
+            .map_err(|err| format!("bad amount \"{}\": {}", amount, err))?;
+        match name {
+            "add" => Ok(Operation::Add(amount)),
+            "subtract" => Ok(Operation::Subtract(amount)),
+            "set" => Ok(Operation::Set(amount)),
+            "multiply" => Ok(Operation::Multiply(amount)),
+            _ => Err(format!("unknown operation \"{}\"", name)),
+        }
+    }
+}
+
+// what each command does to a light's brightness, results are clamped to min..=max
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Semantics {
+    pub on: Operation,
+    pub off: Operation,
+    pub toggle: Operation,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Default for Semantics {
+    fn default() -> Self {
+        // the rules from part two of the puzzle
+        Self {
+            on: Operation::Add(1),
+            off: Operation::Subtract(1),
+            toggle: Operation::Add(2),
+            min: 0,
+            max: u32::MAX,
+        }
+    }
+}
+
+impl Semantics {
+    pub fn apply(&self, command: GridCommand, value: u32) -> u32 {
+        let operation = match command {
+            GridCommand::On => self.on,
+            GridCommand::Off => self.off,
+            GridCommand::Toggle => self.toggle,
+        };
+        operation.apply(value).clamp(self.min, self.max)
+    }
+}
+
+impl FromStr for Semantics {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // comma separated overrides of the part two rules, e.g. "toggle=multiply:2,clamp=0:10"
+        let mut semantics = Semantics::default();
+        for entry in s.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected <key>=<value>, got \"{}\"", entry))?;
+            match key {
+                "on" => semantics.on = value.parse()?,
+                "off" => semantics.off = value.parse()?,
+                "toggle" => semantics.toggle = value.parse()?,
+                "clamp" => {
+                    let bounds = value
+                        .split_once(':')
+                        .and_then(|(min, max)| Some((min.parse().ok()?, max.parse().ok()?)));
+                    let (min, max) = bounds
+                        .ok_or_else(|| format!("expected clamp=<min>:<max>, got \"{}\"", value))?;
+                    semantics.min = min;
+                    semantics.max = max;
+                }
+                _ => return Err(format!("unknown command \"{}\"", key)),
+            }
+        }
+        if semantics.min > semantics.max {
+            return Err(format!(
+                "clamp range {}:{} is empty",
+                semantics.min, semantics.max
+            ));
+        }
+        Ok(semantics)
+    }
+}
+
+// a brightness grid that can set every light in an instruction's rectangle to a new value
+pub trait BrightnessGrid: LightGrid {
+    fn filled(rows: usize, columns: usize, value: u32) -> Self;
+    fn update(&mut self, instruction: &GridInstruction, f: impl Fn(u32) -> u32);
+}
+
+impl BrightnessGrid for DynamicGrid {
+    fn filled(rows: usize, columns: usize, value: u32) -> Self {
+        DynamicGrid::filled(rows, columns, value)
+    }
+
+    fn update(&mut self, instruction: &GridInstruction, f: impl Fn(u32) -> u32) {
+        let (r0, c0) = instruction.start_pos;
+        let (r1, c1) = instruction.end_pos;
+        for row in r0..r1 + 1 {
+            for col in c0..c1 + 1 {
+                self[(row, col)] = f(self[(row, col)]);
+            }
+        }
+    }
+}
+
+impl BrightnessGrid for CompressedGrid<u32> {
+    fn filled(rows: usize, columns: usize, value: u32) -> Self {
+        CompressedGrid::filled(rows, columns, value)
+    }
+
+    fn update(&mut self, instruction: &GridInstruction, f: impl Fn(u32) -> u32) {
+        CompressedGrid::update(self, instruction, |block| *block = f(*block));
+    }
+}
+
+// a brightness grid whose lights follow a semantics table instead of the fixed part two rules,
+// the table is kept once for the grid rather than once per light
+pub struct SemanticGrid<G> {
+    grid: G,
+    semantics: Semantics,
+}
+
+impl<G: BrightnessGrid> SemanticGrid<G> {
+    pub fn new(rows: usize, columns: usize, semantics: Semantics) -> Self {
+        // lights start at the bottom of the clamp range
+        let grid = G::filled(rows, columns, semantics.min);
+        Self { grid, semantics }
+    }
+
+    pub fn grid(&self) -> &G {
+        &self.grid
+    }
+}
+
+impl<G: BrightnessGrid> LightGrid for SemanticGrid<G> {
+    fn count(&self) -> u64 {
+        self.grid.count()
+    }
+
+    fn execute(&mut self, instruction: &GridInstruction) {
+        let (semantics, command) = (self.semantics, instruction.command);
+        self.grid
+            .update(instruction, |value| semantics.apply(command, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use crate::test::random_instructions;
+
+    #[test]
+    fn test_default_is_part_two() {
+        let instructions = random_instructions(50, 20, 11);
+        let mut reference = DynamicGrid::new(20, 20);
+        let mut grid = SemanticGrid::<DynamicGrid>::new(20, 20, Semantics::default());
+        for instruction in instructions.iter() {
+            reference.execute(instruction);
+            grid.execute(instruction);
+            assert_eq!(grid.grid().as_slice(), reference.as_slice());
+        }
+        let mut grid = SemanticGrid::<CompressedGrid<u32>>::new(1000, 1000, "".parse().unwrap());
+        for instruction in parse(include_str!("../input.txt")).unwrap().iter() {
+            grid.execute(instruction);
+        }
+        assert_eq!(grid.count(), 14687245);
+    }
+
+    #[test]
+    fn test_custom_semantics() {
+        let semantics: Semantics = "on=set:5, toggle=multiply:3, clamp=1:12".parse().unwrap();
+        assert_eq!(semantics.off, Operation::Subtract(1));
+        let mut grid = SemanticGrid::<DynamicGrid>::new(1, 3, semantics);
+        let mut compressed = SemanticGrid::<CompressedGrid<u32>>::new(1, 3, semantics);
+        assert_eq!(grid.count(), 3);
+        for line in [
+            "turn on 0,0 through 0,1",
+            "toggle 0,1 through 0,2",
+            "turn off 0,2 through 0,2",
+        ] {
+            grid.execute(&line.parse().unwrap());
+            compressed.execute(&line.parse().unwrap());
+        }
+        // 5, then 5 * 3 clamped to 12, then 1 * 3 - 1
+        assert_eq!(grid.grid().as_slice(), &[5, 12, 2]);
+        assert_eq!(compressed.count(), 19);
+    }
+
+    #[test]
+    fn test_count_past_u32() {
+        let semantics: Semantics = "on=set:100000".parse().unwrap();
+        let instruction = "turn on 0,0 through 999,999".parse().unwrap();
+        let mut grid = SemanticGrid::<DynamicGrid>::new(1000, 1000, semantics);
+        let mut compressed = SemanticGrid::<CompressedGrid<u32>>::new(1000, 1000, semantics);
+        grid.execute(&instruction);
+        compressed.execute(&instruction);
+        assert_eq!(grid.count(), 100_000_000_000);
+        assert_eq!(compressed.count(), 100_000_000_000);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("on=add".parse::<Semantics>().is_err());
+        assert!("on=divide:2".parse::<Semantics>().is_err());
+        assert!("dim=add:1".parse::<Semantics>().is_err());
+        assert!("clamp=5:1".parse::<Semantics>().is_err());
+        assert!("clamp=5".parse::<Semantics>().is_err());
+        assert_eq!("toggle=add:2".parse(), Ok(Semantics::default()));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Grid;
+
+// characters used for text previews, from dark to brightest
+pub const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+pub fn shade(value: u64, max_value: u64) -> char {
+    // picks the ramp character for value out of max_value, anything above zero gets at least the
+    // first non-blank character
+    let levels = (ASCII_RAMP.len() - 1) as u64;
+    let level = match value {
+        0 => 0,
+        x => (x * levels).div_ceil(max_value.max(1)).clamp(1, levels),
+    };
+    ASCII_RAMP[level as usize] as char
+}
+
+pub fn to_pgm(grid: &Grid<u32>, max_value: u32) -> String {
+    // writes a plain (P2) greyscale image, cells are written as they are and max_value is white
+    let mut image = format!("P2\n{} {}\n{}\n", grid.columns(), grid.rows(), max_value);
+    for row in grid.row_slices() {
+        let line: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+        image.push_str(&line.join(" "));
+        image.push('\n');
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shade() {
+        assert_eq!(shade(0, 10), ' ');
+        assert_eq!(shade(1, 1000), '.');
+        assert_eq!(shade(10, 10), '@');
+        assert_eq!(shade(5, 10), '+');
+        assert_eq!(shade(3, 0), '@');
+    }
+
+    #[test]
+    fn test_pgm() {
+        let grid = Grid::from_vec(2, 2, vec![0, 1, 2, 3]).unwrap();
+        assert_eq!(to_pgm(&grid, 3), "P2\n2 2\n3\n0 1\n2 3\n");
+        assert_eq!(to_pgm(&Grid::new(0, 0), 1), "P2\n0 0\n1\n");
+    }
+}
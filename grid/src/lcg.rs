@@ -0,0 +1,44 @@
+// a small linear congruential generator so randomized tests don't need a rand dependency, the
+// same seed always gives the same numbers
+#[derive(Clone, Debug)]
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn below(&mut self, limit: usize) -> usize {
+        // uses the high bits, the low bits of an lcg repeat after a handful of steps
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) as usize % limit
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeatable() {
+        let draws = |seed| {
+            let mut lcg = Lcg::new(seed);
+            (0..100).map(|_| lcg.below(6)).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+        assert!(draws(7).iter().all(|&x| x < 6));
+        // every value turns up, the low bits alone would cycle through only a few
+        assert!((0..6).all(|x| draws(7).contains(&x)));
+        assert_eq!(*Lcg::new(1).choose(&['x']), 'x');
+    }
+}
@@ -0,0 +1,401 @@
+pub mod image;
+#[cfg(any(test, feature = "test-util"))]
+pub mod lcg;
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    slice,
+};
+
+// (row, column), rows count down from the top
+pub type GridIndex = (usize, usize);
+
+// a fixed size 2D grid stored row by row
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T> Grid<T>
+where
+    T: Default + Clone,
+{
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self::filled(rows, columns, T::default())
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; rows * columns],
+            rows,
+            columns,
+        }
+    }
+
+    pub fn resize(&mut self, rows: usize, columns: usize, value: T) {
+        // cells that fit in both sizes stay at the same index, new ones are filled with value
+        let mut cells = Vec::with_capacity(rows * columns);
+        for row in 0..rows {
+            for col in 0..columns {
+                let cell = match self.get((row, col)) {
+                    Some(x) => x.clone(),
+                    None => value.clone(),
+                };
+                cells.push(cell);
+            }
+        }
+        *self = Self {
+            cells,
+            rows,
+            columns,
+        };
+    }
+
+    pub fn transpose(&self) -> Self {
+        self.remap(self.columns, self.rows, |(row, col)| (col, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        // the left column becomes the top row
+        let last_row = self.rows.wrapping_sub(1);
+        self.remap(self.columns, self.rows, |(row, col)| (last_row - col, row))
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let last_column = self.columns.wrapping_sub(1);
+        self.remap(self.columns, self.rows, |(row, col)| {
+            (col, last_column - row)
+        })
+    }
+
+    fn remap(&self, rows: usize, columns: usize, source: impl Fn(GridIndex) -> GridIndex) -> Self {
+        // builds a grid where every cell is copied from the source position in this one
+        let cells = (0..rows)
+            .flat_map(|row| (0..columns).map(move |col| (row, col)))
+            .map(|position| self[source(position)].clone())
+            .collect();
+        Self {
+            cells,
+            rows,
+            columns,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(rows: usize, columns: usize, cells: Vec<T>) -> Result<Self, String> {
+        if cells.len() != rows * columns {
+            return Err(format!(
+                "{} cells can't fill a {}x{} grid",
+                cells.len(),
+                rows,
+                columns
+            ));
+        }
+        Ok(Self {
+            cells,
+            rows,
+            columns,
+        })
+    }
+
+    pub fn parse_chars(
+        input: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, String> {
+        // one row per line, blank lines are skipped and every row has to be the same length
+        let lines: Vec<&str> = input
+            .lines()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .collect();
+        let columns = lines.first().map_or(0, |x| x.chars().count());
+        let mut cells = Vec::with_capacity(lines.len() * columns);
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != columns {
+                return Err(format!(
+                    "row {} has {} cells, expected {}",
+                    row + 1,
+                    line.chars().count(),
+                    columns
+                ));
+            }
+            for c in line.chars() {
+                let value = cell(c)
+                    .ok_or_else(|| format!("row {}: unexpected character {:?}", row + 1, c))?;
+                cells.push(value);
+            }
+        }
+        Self::from_vec(lines.len(), columns, cells)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn get(&self, (r, c): GridIndex) -> Option<&T> {
+        if r < self.rows && c < self.columns {
+            self.cells.get(r * self.columns + c)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (r, c): GridIndex) -> Option<&mut T> {
+        if r < self.rows && c < self.columns {
+            self.cells.get_mut(r * self.columns + c)
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (GridIndex, &T)> {
+        // row-major like iter, paired with each cell's position
+        let columns = self.columns;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(n, x)| ((n / columns, n % columns), x))
+    }
+
+    pub fn row(&self, r: usize) -> Option<&[T]> {
+        if r < self.rows {
+            Some(&self.cells[r * self.columns..(r + 1) * self.columns])
+        } else {
+            None
+        }
+    }
+
+    pub fn row_slices(&self) -> impl Iterator<Item = &[T]> {
+        // chunks of zero panic, a grid without columns has no cells to hand out anyway
+        self.cells.chunks(self.columns.max(1))
+    }
+
+    pub fn column(&self, c: usize) -> Option<impl Iterator<Item = &T>> {
+        if c < self.columns {
+            Some(self.cells.iter().skip(c).step_by(self.columns))
+        } else {
+            None
+        }
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            columns: self.columns,
+        }
+    }
+
+    fn offsets(
+        &self,
+        (row, col): GridIndex,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = GridIndex> {
+        let (rows, columns) = (self.rows, self.columns);
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let r = row.checked_add_signed(dr)?;
+            let c = col.checked_add_signed(dc)?;
+            if r < rows && c < columns {
+                Some((r, c))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn neighbors4(&self, position: GridIndex) -> impl Iterator<Item = GridIndex> {
+        // up, left, right and down, skipping any past the edge
+        self.offsets(position, &[(-1, 0), (0, -1), (0, 1), (1, 0)])
+    }
+
+    pub fn neighbors8(&self, position: GridIndex) -> impl Iterator<Item = GridIndex> {
+        // the surrounding ring in reading order, skipping any past the edge
+        self.offsets(
+            position,
+            &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        )
+    }
+}
+
+impl<T> Index<GridIndex> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: GridIndex) -> &Self::Output {
+        match self.get(position) {
+            Some(x) => x,
+            None => panic!("Index Out of Bounds Error"),
+        }
+    }
+}
+
+impl<T> IndexMut<GridIndex> for Grid<T> {
+    fn index_mut(&mut self, position: GridIndex) -> &mut Self::Output {
+        match self.get_mut(position) {
+            Some(x) => x,
+            None => panic!("Index Out of Bounds Error"),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // cells back to back with a line per row, the inverse of parse_chars for char maps
+        for (n, row) in self.row_slices().enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse_chars(input, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid: Grid<u8> = Grid::new(2, 3);
+        assert_eq!((grid.rows(), grid.columns()), (2, 3));
+        grid[(1, 2)] = 7;
+        assert_eq!(grid.get((1, 2)), Some(&7));
+        assert_eq!(grid.as_slice(), &[0, 0, 0, 0, 0, 7]);
+        // a column past the end would otherwise wrap onto the next row
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get_mut((2, 0)), None);
+        *grid.get_mut((0, 0)).unwrap() = 1;
+        assert_eq!(grid[(0, 0)], 1);
+        assert!(Grid::from_vec(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Index Out of Bounds Error")]
+    fn test_index_out_of_bounds() {
+        let mut grid: Grid<u8> = Grid::new(2, 2);
+        grid[(0, 2)] = 1;
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.iter().sum::<u32>(), 21);
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(1).unwrap().collect::<Vec<_>>(), vec![&2, &5]);
+        assert!(grid.column(3).is_none());
+        assert_eq!(grid.row_slices().count(), 2);
+        let positions: Vec<GridIndex> = grid
+            .indexed_iter()
+            .filter(|(_, &x)| x % 2 == 0)
+            .map(|(position, _)| position)
+            .collect();
+        assert_eq!(positions, vec![(0, 1), (1, 0), (1, 2)]);
+        assert_eq!((&grid).into_iter().max(), Some(&6));
+        assert_eq!(grid.map(|x| x * 10)[(1, 1)], 50);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid: Grid<u8> = Grid::new(3, 4);
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbors8((2, 3)).collect::<Vec<_>>(),
+            vec![(1, 2), (1, 3), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36");
+        assert_eq!(grid.rotate_clockwise().to_string(), "41\n52\n63");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "36\n25\n14");
+        let full_turn = grid
+            .rotate_clockwise()
+            .rotate_clockwise()
+            .rotate_clockwise()
+            .rotate_clockwise();
+        assert_eq!(full_turn, grid);
+        assert_eq!(Grid::<u8>::new(0, 0).rotate_clockwise(), Grid::new(0, 0));
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut grid = digits("12\n34");
+        grid.resize(3, 3, 0);
+        assert_eq!(grid.to_string(), "120\n340\n000");
+        grid.resize(1, 2, 9);
+        assert_eq!(grid.to_string(), "12");
+    }
+
+    #[test]
+    fn test_parse_chars() {
+        let map = "#.#\n.#.\n";
+        let grid = Grid::parse_chars(map, |c| match c {
+            '#' => Some('#'),
+            '.' => Some('.'),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(grid.to_string(), map.trim_end());
+        assert!(digits("").as_slice().is_empty());
+        assert!(Grid::parse_chars("12\n3", |c| c.to_digit(10)).is_err());
+        assert!(Grid::parse_chars("1x", |c| c.to_digit(10)).is_err());
+    }
+}